@@ -0,0 +1,246 @@
+use std::fmt;
+use web_sys::WebGl2RenderingContext as WebGl;
+use super::{LOWER_LEFT_EXT, NEGATIVE_ONE_TO_ONE_EXT, ZERO_TO_ONE_EXT};
+use super::{Buffer, Program, Shader, Texture, UniformLocation, UniformValue, VertexArray};
+
+/// One `Backend` method invocation with its arguments, objects created by
+/// the call are stored alongside.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Call {
+    Viewport(i32, i32, i32, i32),
+    ClearColor(f32, f32, f32, f32),
+    ClearDepth(f32),
+    Clear(u32),
+    Enable(u32),
+    Disable(u32),
+    DepthFunc(u32),
+    BlendFunc(u32, u32),
+    ClipControl(u32, u32),
+    //
+    CreateBuffer(Buffer),
+    DeleteBuffer(Option<Buffer>),
+    BindBuffer(u32, Option<Buffer>),
+    BufferData(u32, Vec<u8>, u32),
+    BufferSubData(u32, i32, Vec<u8>),
+    BindBufferBase(u32, u32, Option<Buffer>),
+    //
+    CreateVertexArray(VertexArray),
+    DeleteVertexArray(Option<VertexArray>),
+    BindVertexArray(Option<VertexArray>),
+    EnableVertexAttribArray(u32),
+    VertexAttribPointer(u32, i32, u32, bool, i32, i32),
+    //
+    CreateShader(u32, Shader),
+    ShaderSource(Shader, String),
+    CompileShader(Shader),
+    DeleteShader(Option<Shader>),
+    CreateProgram(Program),
+    AttachShader(Program, Shader),
+    LinkProgram(Program),
+    DeleteProgram(Option<Program>),
+    UseProgram(Option<Program>),
+    BindAttribLocation(Program, u32, String),
+    GetUniformLocation(Program, String, Option<UniformLocation>),
+    UniformBlockBinding(Program, u32, u32),
+    //
+    Uniform1i(Option<UniformLocation>, i32),
+    UniformMatrix4fv(Option<UniformLocation>, bool, Vec<f32>),
+    Uniform(Option<UniformLocation>, UniformValue),
+    //
+    CreateTexture(Texture),
+    DeleteTexture(Option<Texture>),
+    BindTexture(u32, Option<Texture>),
+    ActiveTexture(u32),
+    TexImage2d {
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        type_: u32,
+        pixels: Option<Vec<u8>>,
+    },
+    TexParameteri(u32, u32, i32),
+    GenerateMipmap(u32),
+    LoadImage(Texture, String),
+    //
+    DrawArrays(u32, i32, i32),
+    DrawElements(u32, i32, u32, i32),
+}
+
+const NAMES: &[(u32, &str)] = &[
+    (WebGl::ARRAY_BUFFER, "ARRAY_BUFFER"),
+    (WebGl::ELEMENT_ARRAY_BUFFER, "ELEMENT_ARRAY_BUFFER"),
+    (WebGl::UNIFORM_BUFFER, "UNIFORM_BUFFER"),
+    (WebGl::STATIC_DRAW, "STATIC_DRAW"),
+    (WebGl::DYNAMIC_DRAW, "DYNAMIC_DRAW"),
+    (WebGl::STREAM_DRAW, "STREAM_DRAW"),
+    (WebGl::BYTE, "BYTE"),
+    (WebGl::UNSIGNED_BYTE, "UNSIGNED_BYTE"),
+    (WebGl::SHORT, "SHORT"),
+    (WebGl::UNSIGNED_SHORT, "UNSIGNED_SHORT"),
+    (WebGl::INT, "INT"),
+    (WebGl::UNSIGNED_INT, "UNSIGNED_INT"),
+    (WebGl::FLOAT, "FLOAT"),
+    (WebGl::VERTEX_SHADER, "VERTEX_SHADER"),
+    (WebGl::FRAGMENT_SHADER, "FRAGMENT_SHADER"),
+    (WebGl::TEXTURE_2D, "TEXTURE_2D"),
+    (WebGl::RGB, "RGB"),
+    (WebGl::RGBA, "RGBA"),
+    (WebGl::DEPTH_TEST, "DEPTH_TEST"),
+    (WebGl::BLEND, "BLEND"),
+    (WebGl::CULL_FACE, "CULL_FACE"),
+    (WebGl::NEVER, "NEVER"),
+    (WebGl::LESS, "LESS"),
+    (WebGl::EQUAL, "EQUAL"),
+    (WebGl::LEQUAL, "LEQUAL"),
+    (WebGl::GREATER, "GREATER"),
+    (WebGl::NOTEQUAL, "NOTEQUAL"),
+    (WebGl::GEQUAL, "GEQUAL"),
+    (WebGl::ALWAYS, "ALWAYS"),
+    (WebGl::SRC_ALPHA, "SRC_ALPHA"),
+    (WebGl::ONE_MINUS_SRC_ALPHA, "ONE_MINUS_SRC_ALPHA"),
+    (WebGl::TEXTURE_MAG_FILTER, "TEXTURE_MAG_FILTER"),
+    (WebGl::TEXTURE_MIN_FILTER, "TEXTURE_MIN_FILTER"),
+    (WebGl::TEXTURE_WRAP_S, "TEXTURE_WRAP_S"),
+    (WebGl::TEXTURE_WRAP_T, "TEXTURE_WRAP_T"),
+    (WebGl::NEAREST, "NEAREST"),
+    (WebGl::LINEAR, "LINEAR"),
+    (WebGl::LINEAR_MIPMAP_LINEAR, "LINEAR_MIPMAP_LINEAR"),
+    (WebGl::REPEAT, "REPEAT"),
+    (WebGl::CLAMP_TO_EDGE, "CLAMP_TO_EDGE"),
+    (LOWER_LEFT_EXT, "LOWER_LEFT_EXT"),
+    (NEGATIVE_ONE_TO_ONE_EXT, "NEGATIVE_ONE_TO_ONE_EXT"),
+    (ZERO_TO_ONE_EXT, "ZERO_TO_ONE_EXT"),
+];
+
+const MODES: &[(u32, &str)] = &[
+    (WebGl::POINTS, "POINTS"),
+    (WebGl::LINES, "LINES"),
+    (WebGl::LINE_LOOP, "LINE_LOOP"),
+    (WebGl::LINE_STRIP, "LINE_STRIP"),
+    (WebGl::TRIANGLES, "TRIANGLES"),
+    (WebGl::TRIANGLE_STRIP, "TRIANGLE_STRIP"),
+    (WebGl::TRIANGLE_FAN, "TRIANGLE_FAN"),
+];
+
+const BITS: &[(u32, &str)] = &[
+    (WebGl::COLOR_BUFFER_BIT, "COLOR_BUFFER_BIT"),
+    (WebGl::DEPTH_BUFFER_BIT, "DEPTH_BUFFER_BIT"),
+    (WebGl::STENCIL_BUFFER_BIT, "STENCIL_BUFFER_BIT"),
+];
+
+fn lookup(table: &[(u32, &str)], value: u32) -> String {
+    table.iter()
+        .find(|(v, _)| *v == value)
+        .map_or_else(|| format!("0x{:04x}", value), |(_, name)| (*name).to_owned())
+}
+
+fn name(value: u32) -> String {
+    if (WebGl::TEXTURE0..=WebGl::TEXTURE31).contains(&value) {
+        return format!("TEXTURE{}", value - WebGl::TEXTURE0);
+    }
+    lookup(NAMES, value)
+}
+
+fn bits(mask: u32) -> String {
+    let names: Vec<&str> = BITS.iter()
+        .filter(|(bit, _)| mask & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    if names.is_empty() { "0".to_owned() } else { names.join(" | ") }
+}
+
+fn opt<T: fmt::Debug>(value: &Option<T>) -> String {
+    value.as_ref().map_or("null".to_owned(), |v| format!("{:?}", v))
+}
+
+/// Length and FNV-1a hash, enough to spot changed data in a diff.
+fn blob(data: &[u8]) -> String {
+    let hash = data.iter().fold(0x811c9dc5u32, |h, b| (h ^ *b as u32).wrapping_mul(0x01000193));
+    format!("<{} bytes fnv1a={:08x}>", data.len(), hash)
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Call::Viewport(x, y, w, h) => write!(f, "viewport({}, {}, {}, {})", x, y, w, h),
+            Call::ClearColor(r, g, b, a) => write!(f, "clear_color({:?}, {:?}, {:?}, {:?})", r, g, b, a),
+            Call::ClearDepth(d) => write!(f, "clear_depth({:?})", d),
+            Call::Clear(mask) => write!(f, "clear({})", bits(*mask)),
+            Call::Enable(cap) => write!(f, "enable({})", name(*cap)),
+            Call::Disable(cap) => write!(f, "disable({})", name(*cap)),
+            Call::DepthFunc(func) => write!(f, "depth_func({})", name(*func)),
+            Call::BlendFunc(s, d) => write!(f, "blend_func({}, {})", name(*s), name(*d)),
+            Call::ClipControl(o, d) => write!(f, "clip_control({}, {})", name(*o), name(*d)),
+            Call::CreateBuffer(b) => write!(f, "create_buffer() -> {:?}", b),
+            Call::DeleteBuffer(b) => write!(f, "delete_buffer({})", opt(b)),
+            Call::BindBuffer(t, b) => write!(f, "bind_buffer({}, {})", name(*t), opt(b)),
+            Call::BufferData(t, data, usage) => {
+                write!(f, "buffer_data({}, {}, {})", name(*t), blob(data), name(*usage))
+            }
+            Call::BufferSubData(t, offset, data) => {
+                write!(f, "buffer_sub_data({}, {}, {})", name(*t), offset, blob(data))
+            }
+            Call::BindBufferBase(t, i, b) => write!(f, "bind_buffer_base({}, {}, {})", name(*t), i, opt(b)),
+            Call::CreateVertexArray(v) => write!(f, "create_vertex_array() -> {:?}", v),
+            Call::DeleteVertexArray(v) => write!(f, "delete_vertex_array({})", opt(v)),
+            Call::BindVertexArray(v) => write!(f, "bind_vertex_array({})", opt(v)),
+            Call::EnableVertexAttribArray(i) => write!(f, "enable_vertex_attrib_array({})", i),
+            Call::VertexAttribPointer(i, size, type_, normalized, stride, offset) => write!(
+                f, "vertex_attrib_pointer({}, {}, {}, {}, {}, {})",
+                i, size, name(*type_), normalized, stride, offset,
+            ),
+            Call::CreateShader(t, s) => write!(f, "create_shader({}) -> {:?}", name(*t), s),
+            Call::ShaderSource(s, source) => {
+                write!(f, "shader_source({:?}, {})", s, blob(source.as_bytes()))?;
+                for line in source.lines() {
+                    write!(f, "\n    | {}", line.trim_end())?;
+                }
+                Ok(())
+            }
+            Call::CompileShader(s) => write!(f, "compile_shader({:?})", s),
+            Call::DeleteShader(s) => write!(f, "delete_shader({})", opt(s)),
+            Call::CreateProgram(p) => write!(f, "create_program() -> {:?}", p),
+            Call::AttachShader(p, s) => write!(f, "attach_shader({:?}, {:?})", p, s),
+            Call::LinkProgram(p) => write!(f, "link_program({:?})", p),
+            Call::DeleteProgram(p) => write!(f, "delete_program({})", opt(p)),
+            Call::UseProgram(p) => write!(f, "use_program({})", opt(p)),
+            Call::BindAttribLocation(p, i, n) => write!(f, "bind_attrib_location({:?}, {}, {:?})", p, i, n),
+            Call::GetUniformLocation(p, n, l) => {
+                write!(f, "get_uniform_location({:?}, {:?}) -> {}", p, n, opt(l))
+            }
+            Call::UniformBlockBinding(p, i, b) => write!(f, "uniform_block_binding({:?}, {}, {})", p, i, b),
+            Call::Uniform1i(l, x) => write!(f, "uniform1i({}, {})", opt(l), x),
+            Call::UniformMatrix4fv(l, t, data) => {
+                write!(f, "uniform_matrix4fv({}, {}, {:?})", opt(l), t, data)
+            }
+            Call::Uniform(l, value) => write!(f, "uniform({}, {:?})", opt(l), value),
+            Call::CreateTexture(t) => write!(f, "create_texture() -> {:?}", t),
+            Call::DeleteTexture(t) => write!(f, "delete_texture({})", opt(t)),
+            Call::BindTexture(target, t) => write!(f, "bind_texture({}, {})", name(*target), opt(t)),
+            Call::ActiveTexture(unit) => write!(f, "active_texture({})", name(*unit)),
+            Call::TexImage2d { target, level, internal_format, width, height, format, type_, pixels } => write!(
+                f, "tex_image_2d({}, {}, {}, {}, {}, {}, {}, {})",
+                name(*target), level, name(*internal_format as u32), width, height,
+                name(*format), name(*type_),
+                pixels.as_ref().map_or("null".to_owned(), |p| blob(p)),
+            ),
+            Call::TexParameteri(target, pname, param) => write!(
+                f, "tex_parameteri({}, {}, {})",
+                name(*target), name(*pname), name(*param as u32),
+            ),
+            Call::GenerateMipmap(target) => write!(f, "generate_mipmap({})", name(*target)),
+            Call::LoadImage(t, url) => write!(f, "load_image({:?}, {:?})", t, url),
+            Call::DrawArrays(mode, first, count) => {
+                write!(f, "draw_arrays({}, {}, {})", lookup(MODES, *mode), first, count)
+            }
+            Call::DrawElements(mode, count, type_, offset) => write!(
+                f, "draw_elements({}, {}, {}, {})",
+                lookup(MODES, *mode), count, name(*type_), offset,
+            ),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use super::{Buffer, Program, Shader, Texture, UniformLocation, UniformValue, VertexArray};
+
+/// Name, GL type and array size of an active uniform or attribute.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ActiveInfo {
+    pub name: String,
+    pub type_: u32,
+    pub size: i32,
+}
+
+// `EXT_clip_control` enums, web-sys has no constants for them.
+pub const LOWER_LEFT_EXT: u32 = 0x8CA1;
+pub const NEGATIVE_ONE_TO_ONE_EXT: u32 = 0x935E;
+pub const ZERO_TO_ONE_EXT: u32 = 0x935F;
+
+/// The subset of WebGL2 the engine draws with. Method names and arguments
+/// follow `WebGl2RenderingContext`, enums are the same GL constants.
+pub trait Backend {
+    fn viewport(&self, x: i32, y: i32, width: i32, height: i32);
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32);
+    fn clear_depth(&self, depth: f32);
+    fn clear(&self, mask: u32);
+    fn enable(&self, cap: u32);
+    fn disable(&self, cap: u32);
+    fn depth_func(&self, func: u32);
+    fn blend_func(&self, sfactor: u32, dfactor: u32);
+    /// `clipControlEXT`, returns false and does nothing when the context
+    /// lacks `EXT_clip_control`.
+    fn clip_control(&self, origin: u32, depth: u32) -> bool;
+
+    fn create_buffer(&self) -> Option<Buffer>;
+    fn delete_buffer(&self, buffer: Option<&Buffer>);
+    fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>);
+    fn buffer_data(&self, target: u32, data: &[u8], usage: u32);
+    fn buffer_sub_data(&self, target: u32, offset: i32, data: &[u8]);
+    fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<&Buffer>);
+
+    fn create_vertex_array(&self) -> Option<VertexArray>;
+    fn delete_vertex_array(&self, vao: Option<&VertexArray>);
+    fn bind_vertex_array(&self, vao: Option<&VertexArray>);
+    fn enable_vertex_attrib_array(&self, index: u32);
+    fn vertex_attrib_pointer(
+        &self,
+        index: u32,
+        size: i32,
+        type_: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    );
+
+    fn create_shader(&self, type_: u32) -> Option<Shader>;
+    fn shader_source(&self, shader: &Shader, source: &str);
+    fn compile_shader(&self, shader: &Shader);
+    fn shader_compile_status(&self, shader: &Shader) -> bool;
+    fn shader_info_log(&self, shader: &Shader) -> Option<String>;
+    fn delete_shader(&self, shader: Option<&Shader>);
+
+    fn create_program(&self) -> Option<Program>;
+    fn attach_shader(&self, program: &Program, shader: &Shader);
+    fn link_program(&self, program: &Program);
+    fn program_link_status(&self, program: &Program) -> bool;
+    fn program_info_log(&self, program: &Program) -> Option<String>;
+    fn delete_program(&self, program: Option<&Program>);
+    fn use_program(&self, program: Option<&Program>);
+    fn bind_attrib_location(&self, program: &Program, index: u32, name: &str);
+    fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation>;
+    fn get_attrib_location(&self, program: &Program, name: &str) -> i32;
+    /// Active uniforms of a linked program, arrays are named `name[0]`.
+    fn active_uniforms(&self, program: &Program) -> Vec<ActiveInfo>;
+    fn active_attributes(&self, program: &Program) -> Vec<ActiveInfo>;
+    /// Names of the active uniform blocks, in block index order.
+    fn active_uniform_blocks(&self, program: &Program) -> Vec<String>;
+    fn uniform_block_binding(&self, program: &Program, block_index: u32, binding: u32);
+
+    fn uniform1i(&self, location: Option<&UniformLocation>, x: i32);
+    fn uniform_matrix4fv(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]);
+    /// Issues the `uniform*` call matching the value's type.
+    fn uniform(&self, location: Option<&UniformLocation>, value: &UniformValue);
+
+    fn create_texture(&self) -> Option<Texture>;
+    fn delete_texture(&self, texture: Option<&Texture>);
+    fn bind_texture(&self, target: u32, texture: Option<&Texture>);
+    fn active_texture(&self, unit: u32);
+    #[allow(clippy::too_many_arguments)]
+    fn tex_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        type_: u32,
+        pixels: Option<&[u8]>,
+    );
+    fn tex_parameteri(&self, target: u32, pname: u32, param: i32);
+    fn generate_mipmap(&self, target: u32);
+    /// Fetches an image and uploads it into `texture` with mipmaps once it
+    /// has loaded, the texture keeps its current contents until then.
+    fn load_image(&self, texture: &Texture, url: &str);
+
+    fn draw_arrays(&self, mode: u32, first: i32, count: i32);
+    fn draw_elements(&self, mode: u32, count: i32, type_: u32, offset: i32);
+}
+
+/// Little-endian bytes of a float slice, as `buffer_data` expects.
+pub fn f32_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
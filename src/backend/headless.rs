@@ -0,0 +1,554 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use web_sys::WebGl2RenderingContext as WebGl;
+use super::{type_from_name, ActiveInfo, Backend, Buffer, Call, Program, Shader, Texture};
+use super::{UniformLocation, UniformValue, VertexArray};
+
+/// Simulated GL object, enough to answer the queries the engine makes.
+#[derive(Clone, Debug)]
+pub enum Object {
+    Buffer,
+    VertexArray,
+    Shader {
+        type_: u32,
+        source: String,
+        compiled: Option<Result<(), String>>,
+    },
+    Program {
+        shaders: Vec<u32>,
+        linked: Option<Result<(), String>>,
+        bindings: Vec<(u32, String)>,
+        uniforms: Vec<ActiveInfo>,
+        blocks: Vec<String>,
+        attributes: Vec<(ActiveInfo, i32)>,
+    },
+    Texture,
+}
+
+/// Backend without a GPU that logs every call, for running the engine
+/// under `cargo test`. Shaders compile unless a line outside a skipped
+/// `#ifdef` starts with `#error`, which produces a driver-style info log
+/// pointing at that line. Every uniform and uniform block declared in the
+/// linked sources and every vertex shader `in` is reported active,
+/// attributes get their `layout` or bound location first.
+#[derive(Default)]
+pub struct Headless {
+    calls: RefCell<Vec<Call>>,
+    objects: RefCell<HashMap<u32, Object>>,
+    locations: RefCell<HashMap<u32, (u32, String)>>,
+    next: Cell<u32>,
+    clip_control: Cell<bool>,
+}
+
+impl Headless {
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.borrow().clone()
+    }
+
+    pub fn take_calls(&self) -> Vec<Call> {
+        self.calls.take()
+    }
+
+    /// Whether `clip_control` acts as if `EXT_clip_control` were present,
+    /// off by default.
+    pub fn set_clip_control(&self, available: bool) {
+        self.clip_control.set(available);
+    }
+
+    /// Objects created and not yet deleted.
+    pub fn objects(&self) -> HashMap<u32, Object> {
+        self.objects.borrow().clone()
+    }
+
+    fn record(&self, call: Call) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    fn alloc(&self, object: Object) -> u32 {
+        let id = self.next.get() + 1;
+        self.next.set(id);
+        self.objects.borrow_mut().insert(id, object);
+        id
+    }
+
+    fn delete(&self, id: Option<u32>) {
+        if let Some(id) = id {
+            self.objects.borrow_mut().remove(&id);
+        }
+    }
+}
+
+/// Lines outside `#ifdef`/`#ifndef` branches not taken, other lines are
+/// blanked so line numbers still match.
+fn active_lines(source: &str) -> Vec<&str> {
+    let mut defined: Vec<&str> = Vec::new();
+    let mut stack: Vec<bool> = Vec::new();
+    source.lines()
+        .map(|line| {
+            let mut words = line.split_whitespace();
+            let active = stack.iter().all(|b| *b);
+            match (words.next(), words.next()) {
+                (Some("#ifdef"), Some(name)) => stack.push(defined.contains(&name)),
+                (Some("#ifndef"), Some(name)) => stack.push(!defined.contains(&name)),
+                (Some("#else"), _) => {
+                    if let Some(top) = stack.last_mut() {
+                        *top = !*top;
+                    }
+                }
+                (Some("#endif"), _) => {
+                    stack.pop();
+                }
+                (Some("#define"), Some(name)) if active => {
+                    defined.push(name);
+                    return line;
+                }
+                _ if active => return line,
+                _ => {}
+            }
+            ""
+        })
+        .collect()
+}
+
+/// Names of `uniform Name {` blocks, the brace may be on the next line.
+fn uniform_blocks(source: &str) -> Vec<String> {
+    let lines = active_lines(source);
+    lines.iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = match line.trim().strip_prefix("layout") {
+                Some(rest) => rest.split_once(')')?.1,
+                None => line,
+            };
+            let mut words = line.split_whitespace();
+            if words.next()? != "uniform" {
+                return None;
+            }
+            let name = words.next()?;
+            let (name, brace) = match name.split_once('{') {
+                Some((name, _)) => (name, true),
+                None => (name, words.next().is_some_and(|w| w.starts_with('{'))),
+            };
+            let brace = brace || lines.get(i + 1).is_some_and(|l| l.trim_start().starts_with('{'));
+            match brace && !name.is_empty() {
+                true => Some(name.to_owned()),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+/// Single-line declarations `[layout(location = N)] <qualifier> [precision]
+/// <type> <name>[[size]];`, blocks and unknown types are skipped.
+fn declarations(source: &str, qualifier: &str) -> Vec<(ActiveInfo, Option<i32>)> {
+    active_lines(source).into_iter()
+        .filter_map(|line| {
+            let mut line = line.trim();
+            let mut location = None;
+            if let Some(rest) = line.strip_prefix("layout") {
+                let (inner, rest) = rest.trim_start().strip_prefix('(')?.split_once(')')?;
+                location = inner.split(',')
+                    .filter_map(|q| q.split_once('='))
+                    .find(|(key, _)| key.trim() == "location")
+                    .and_then(|(_, value)| value.trim().parse().ok());
+                line = rest;
+            }
+            let mut words = line.split(';').next()?.split_whitespace();
+            if words.next()? != qualifier {
+                return None;
+            }
+            let mut words = words.skip_while(|w| matches!(*w, "lowp" | "mediump" | "highp"));
+            let type_ = type_from_name(words.next()?)?;
+            let name = words.next()?;
+            let (name, size) = match name.split_once('[') {
+                Some((name, size)) => (format!("{}[0]", name), size.trim_end_matches(']').parse().ok()?),
+                None => (name.to_owned(), 1),
+            };
+            Some((ActiveInfo { name, type_, size }, location))
+        })
+        .collect()
+}
+
+impl Backend for Headless {
+    fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.record(Call::Viewport(x, y, width, height));
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.record(Call::ClearColor(r, g, b, a));
+    }
+
+    fn clear_depth(&self, depth: f32) {
+        self.record(Call::ClearDepth(depth));
+    }
+
+    fn clear(&self, mask: u32) {
+        self.record(Call::Clear(mask));
+    }
+
+    fn enable(&self, cap: u32) {
+        self.record(Call::Enable(cap));
+    }
+
+    fn disable(&self, cap: u32) {
+        self.record(Call::Disable(cap));
+    }
+
+    fn depth_func(&self, func: u32) {
+        self.record(Call::DepthFunc(func));
+    }
+
+    fn blend_func(&self, sfactor: u32, dfactor: u32) {
+        self.record(Call::BlendFunc(sfactor, dfactor));
+    }
+
+    fn clip_control(&self, origin: u32, depth: u32) -> bool {
+        if self.clip_control.get() {
+            self.record(Call::ClipControl(origin, depth));
+        }
+        self.clip_control.get()
+    }
+
+    fn create_buffer(&self) -> Option<Buffer> {
+        let buffer = Buffer(self.alloc(Object::Buffer));
+        self.record(Call::CreateBuffer(buffer));
+        Some(buffer)
+    }
+
+    fn delete_buffer(&self, buffer: Option<&Buffer>) {
+        self.delete(buffer.map(|b| b.0));
+        self.record(Call::DeleteBuffer(buffer.copied()));
+    }
+
+    fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+        self.record(Call::BindBuffer(target, buffer.copied()));
+    }
+
+    fn buffer_data(&self, target: u32, data: &[u8], usage: u32) {
+        self.record(Call::BufferData(target, data.to_vec(), usage));
+    }
+
+    fn buffer_sub_data(&self, target: u32, offset: i32, data: &[u8]) {
+        self.record(Call::BufferSubData(target, offset, data.to_vec()));
+    }
+
+    fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<&Buffer>) {
+        self.record(Call::BindBufferBase(target, index, buffer.copied()));
+    }
+
+    fn create_vertex_array(&self) -> Option<VertexArray> {
+        let vao = VertexArray(self.alloc(Object::VertexArray));
+        self.record(Call::CreateVertexArray(vao));
+        Some(vao)
+    }
+
+    fn delete_vertex_array(&self, vao: Option<&VertexArray>) {
+        self.delete(vao.map(|v| v.0));
+        self.record(Call::DeleteVertexArray(vao.copied()));
+    }
+
+    fn bind_vertex_array(&self, vao: Option<&VertexArray>) {
+        self.record(Call::BindVertexArray(vao.copied()));
+    }
+
+    fn enable_vertex_attrib_array(&self, index: u32) {
+        self.record(Call::EnableVertexAttribArray(index));
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: u32,
+        size: i32,
+        type_: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    ) {
+        self.record(Call::VertexAttribPointer(index, size, type_, normalized, stride, offset));
+    }
+
+    fn create_shader(&self, type_: u32) -> Option<Shader> {
+        let shader = Shader(self.alloc(Object::Shader {
+            type_,
+            source: String::new(),
+            compiled: None,
+        }));
+        self.record(Call::CreateShader(type_, shader));
+        Some(shader)
+    }
+
+    fn shader_source(&self, shader: &Shader, source: &str) {
+        if let Some(Object::Shader { source: s, .. }) = self.objects.borrow_mut().get_mut(&shader.0) {
+            *s = source.to_owned();
+        }
+        self.record(Call::ShaderSource(*shader, source.to_owned()));
+    }
+
+    fn compile_shader(&self, shader: &Shader) {
+        if let Some(Object::Shader { source, compiled, .. }) = self.objects.borrow_mut().get_mut(&shader.0) {
+            let error = active_lines(source).into_iter().enumerate().find_map(|(i, line)| {
+                let message = line.trim().strip_prefix("#error")?;
+                Some(format!("ERROR: 0:{}: '#error' : {}\n", i + 1, message.trim()))
+            });
+            *compiled = Some(error.map_or(Ok(()), Err));
+        }
+        self.record(Call::CompileShader(*shader));
+    }
+
+    fn shader_compile_status(&self, shader: &Shader) -> bool {
+        matches!(
+            self.objects.borrow().get(&shader.0),
+            Some(Object::Shader { compiled: Some(Ok(())), .. })
+        )
+    }
+
+    fn shader_info_log(&self, shader: &Shader) -> Option<String> {
+        match self.objects.borrow().get(&shader.0)? {
+            Object::Shader { compiled: Some(Err(log)), .. } => Some(log.clone()),
+            Object::Shader { .. } => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn delete_shader(&self, shader: Option<&Shader>) {
+        self.delete(shader.map(|s| s.0));
+        self.record(Call::DeleteShader(shader.copied()));
+    }
+
+    fn create_program(&self) -> Option<Program> {
+        let program = Program(self.alloc(Object::Program {
+            shaders: Vec::new(),
+            linked: None,
+            bindings: Vec::new(),
+            uniforms: Vec::new(),
+            blocks: Vec::new(),
+            attributes: Vec::new(),
+        }));
+        self.record(Call::CreateProgram(program));
+        Some(program)
+    }
+
+    fn attach_shader(&self, program: &Program, shader: &Shader) {
+        if let Some(Object::Program { shaders, .. }) = self.objects.borrow_mut().get_mut(&program.0) {
+            shaders.push(shader.0);
+        }
+        self.record(Call::AttachShader(*program, *shader));
+    }
+
+    fn link_program(&self, program: &Program) {
+        let mut objects = self.objects.borrow_mut();
+        let (attached, bindings) = match objects.get(&program.0) {
+            Some(Object::Program { shaders, bindings, .. }) => (shaders.clone(), bindings.clone()),
+            _ => return,
+        };
+        let mut types = Vec::new();
+        let mut active = Vec::new();
+        let mut blocks = Vec::new();
+        let mut inputs = Vec::new();
+        for id in attached {
+            if let Some(Object::Shader { type_, source, compiled: Some(Ok(())) }) = objects.get(&id) {
+                types.push(*type_);
+                for (info, _) in declarations(source, "uniform") {
+                    if !active.iter().any(|u: &ActiveInfo| u.name == info.name) {
+                        active.push(info);
+                    }
+                }
+                for name in uniform_blocks(source) {
+                    if !blocks.contains(&name) {
+                        blocks.push(name);
+                    }
+                }
+                if *type_ == WebGl::VERTEX_SHADER {
+                    inputs.extend(declarations(source, "in"));
+                }
+            }
+        }
+        let mut attribs: Vec<(ActiveInfo, i32)> = Vec::new();
+        for (info, location) in &inputs {
+            let bound = bindings.iter().find(|(_, n)| *n == info.name).map(|(i, _)| *i as i32);
+            if let Some(location) = location.or(bound) {
+                attribs.push((info.clone(), location));
+            }
+        }
+        for (info, _) in inputs {
+            if !attribs.iter().any(|(a, _)| a.name == info.name) {
+                let free = (0..).find(|i| !attribs.iter().any(|(_, l)| l == i)).unwrap();
+                attribs.push((info, free));
+            }
+        }
+        let result = if types.contains(&WebGl::VERTEX_SHADER) && types.contains(&WebGl::FRAGMENT_SHADER) {
+            Ok(())
+        } else {
+            Err("Program needs a compiled vertex and fragment shader\n".to_owned())
+        };
+        if let Some(Object::Program { linked, uniforms, blocks: b, attributes, .. }) = objects.get_mut(&program.0) {
+            *linked = Some(result);
+            *uniforms = active;
+            *b = blocks;
+            *attributes = attribs;
+        }
+        drop(objects);
+        self.record(Call::LinkProgram(*program));
+    }
+
+    fn program_link_status(&self, program: &Program) -> bool {
+        matches!(
+            self.objects.borrow().get(&program.0),
+            Some(Object::Program { linked: Some(Ok(())), .. })
+        )
+    }
+
+    fn program_info_log(&self, program: &Program) -> Option<String> {
+        match self.objects.borrow().get(&program.0)? {
+            Object::Program { linked: Some(Err(log)), .. } => Some(log.clone()),
+            Object::Program { .. } => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn delete_program(&self, program: Option<&Program>) {
+        self.delete(program.map(|p| p.0));
+        self.record(Call::DeleteProgram(program.copied()));
+    }
+
+    fn use_program(&self, program: Option<&Program>) {
+        self.record(Call::UseProgram(program.copied()));
+    }
+
+    fn bind_attrib_location(&self, program: &Program, index: u32, name: &str) {
+        if let Some(Object::Program { bindings, .. }) = self.objects.borrow_mut().get_mut(&program.0) {
+            bindings.retain(|(_, n)| n != name);
+            bindings.push((index, name.to_owned()));
+        }
+        self.record(Call::BindAttribLocation(*program, index, name.to_owned()));
+    }
+
+    fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+        let known = match self.objects.borrow().get(&program.0) {
+            Some(Object::Program { linked: Some(Ok(())), uniforms, .. }) => {
+                uniforms.iter().any(|u| u.name == name || u.name.strip_suffix("[0]") == Some(name))
+            }
+            _ => false,
+        };
+        let location = if known {
+            let id = self.next.get() + 1;
+            self.next.set(id);
+            self.locations.borrow_mut().insert(id, (program.0, name.to_owned()));
+            Some(UniformLocation(id))
+        } else {
+            None
+        };
+        self.record(Call::GetUniformLocation(*program, name.to_owned(), location));
+        location
+    }
+
+    fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+        match self.objects.borrow().get(&program.0) {
+            Some(Object::Program { linked: Some(Ok(())), attributes, .. }) => attributes.iter()
+                .find(|(a, _)| a.name == name)
+                .map_or(-1, |(_, location)| *location),
+            _ => -1,
+        }
+    }
+
+    fn active_uniforms(&self, program: &Program) -> Vec<ActiveInfo> {
+        match self.objects.borrow().get(&program.0) {
+            Some(Object::Program { linked: Some(Ok(())), uniforms, .. }) => uniforms.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn active_attributes(&self, program: &Program) -> Vec<ActiveInfo> {
+        match self.objects.borrow().get(&program.0) {
+            Some(Object::Program { linked: Some(Ok(())), attributes, .. }) => {
+                attributes.iter().map(|(a, _)| a.clone()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn active_uniform_blocks(&self, program: &Program) -> Vec<String> {
+        match self.objects.borrow().get(&program.0) {
+            Some(Object::Program { linked: Some(Ok(())), blocks, .. }) => blocks.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn uniform_block_binding(&self, program: &Program, block_index: u32, binding: u32) {
+        self.record(Call::UniformBlockBinding(*program, block_index, binding));
+    }
+
+    fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+        self.record(Call::Uniform1i(location.copied(), x));
+    }
+
+    fn uniform_matrix4fv(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]) {
+        self.record(Call::UniformMatrix4fv(location.copied(), transpose, data.to_vec()));
+    }
+
+    fn uniform(&self, location: Option<&UniformLocation>, value: &UniformValue) {
+        self.record(Call::Uniform(location.copied(), value.clone()));
+    }
+
+    fn create_texture(&self) -> Option<Texture> {
+        let texture = Texture(self.alloc(Object::Texture));
+        self.record(Call::CreateTexture(texture));
+        Some(texture)
+    }
+
+    fn delete_texture(&self, texture: Option<&Texture>) {
+        self.delete(texture.map(|t| t.0));
+        self.record(Call::DeleteTexture(texture.copied()));
+    }
+
+    fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+        self.record(Call::BindTexture(target, texture.copied()));
+    }
+
+    fn active_texture(&self, unit: u32) {
+        self.record(Call::ActiveTexture(unit));
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        type_: u32,
+        pixels: Option<&[u8]>,
+    ) {
+        self.record(Call::TexImage2d {
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            format,
+            type_,
+            pixels: pixels.map(|p| p.to_vec()),
+        });
+    }
+
+    fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+        self.record(Call::TexParameteri(target, pname, param));
+    }
+
+    fn generate_mipmap(&self, target: u32) {
+        self.record(Call::GenerateMipmap(target));
+    }
+
+    fn load_image(&self, texture: &Texture, url: &str) {
+        self.record(Call::LoadImage(*texture, url.to_owned()));
+    }
+
+    fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+        self.record(Call::DrawArrays(mode, first, count));
+    }
+
+    fn draw_elements(&self, mode: u32, count: i32, type_: u32, offset: i32) {
+        self.record(Call::DrawElements(mode, count, type_, offset));
+    }
+}
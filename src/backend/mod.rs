@@ -0,0 +1,18 @@
+mod call;
+mod gl;
+mod handle;
+mod headless;
+mod recorder;
+mod trace;
+mod uniform;
+mod webgl;
+
+pub use call::Call;
+pub use gl::{f32_bytes, ActiveInfo, Backend};
+pub use gl::{LOWER_LEFT_EXT, NEGATIVE_ONE_TO_ONE_EXT, ZERO_TO_ONE_EXT};
+pub use handle::{Buffer, Program, Shader, Texture, UniformLocation, VertexArray};
+pub use headless::{Headless, Object};
+pub use recorder::Recorder;
+pub use trace::Trace;
+pub use uniform::{is_sampler, type_from_name, type_name, UniformValue};
+pub use webgl::WebGlBackend;
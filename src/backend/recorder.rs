@@ -0,0 +1,572 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::mem::discriminant;
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as WebGl;
+use super::{ActiveInfo, Backend, Buffer, Call, Program, Shader, Texture, Trace, UniformLocation};
+use super::{UniformValue, VertexArray};
+
+/// Forwards to another backend and logs the calls. Everything up to
+/// `seal` is kept as the setup of every trace, afterwards only calls made
+/// between `start` and `stop` are logged. State set after `seal`, such as
+/// the clear color, buffer uploads and uniforms, is tracked and appended
+/// to the setup of the next capture.
+pub struct Recorder {
+    inner: Rc<dyn Backend>,
+    setup: RefCell<Option<Vec<Call>>>,
+    sealed: RefCell<Vec<Call>>,
+    state: RefCell<State>,
+    prelude: RefCell<Vec<Call>>,
+    frame: RefCell<Option<Vec<Call>>>,
+}
+
+/// Latest state set after `seal`, plus the bindings needed to place
+/// uploads and uniforms.
+#[derive(Default)]
+struct State {
+    /// One call per piece of fixed-function state, see `same_slot`.
+    fixed: Vec<Call>,
+    /// Location lookups, so uniforms of programs looked up late resolve.
+    lookups: BTreeMap<u32, Call>,
+    /// Target and uploads of each buffer since its last `buffer_data`.
+    uploads: BTreeMap<u32, (u32, Vec<Call>)>,
+    /// Latest value by program and location.
+    uniforms: BTreeMap<(u32, u32), Call>,
+    bound: HashMap<u32, Buffer>,
+    vao: Option<VertexArray>,
+    program: Option<Program>,
+}
+
+/// Whether `b` overwrites the state set by `a`.
+fn same_slot(a: &Call, b: &Call) -> bool {
+    match (a, b) {
+        (Call::Enable(x) | Call::Disable(x), Call::Enable(y) | Call::Disable(y)) => x == y,
+        _ => discriminant(a) == discriminant(b),
+    }
+}
+
+impl State {
+    fn update(&mut self, call: &Call, sealed: bool) {
+        match call {
+            Call::BindBuffer(target, buffer) | Call::BindBufferBase(target, _, buffer) => {
+                match buffer {
+                    Some(buffer) => self.bound.insert(*target, *buffer),
+                    None => self.bound.remove(target),
+                };
+            }
+            Call::BindVertexArray(vao) => {
+                // The element buffer binding belongs to the vertex array.
+                self.vao = *vao;
+                self.bound.remove(&WebGl::ELEMENT_ARRAY_BUFFER);
+            }
+            Call::UseProgram(program) => self.program = *program,
+            Call::DeleteBuffer(Some(buffer)) => {
+                self.uploads.remove(&buffer.0);
+            }
+            Call::DeleteProgram(Some(program)) => {
+                self.uniforms.retain(|(p, _), _| *p != program.0);
+                self.lookups.retain(|_, c| {
+                    !matches!(c, Call::GetUniformLocation(p, ..) if p == program)
+                });
+            }
+            _ if !sealed => {}
+            Call::Viewport(..) | Call::ClearColor(..) | Call::ClearDepth(..) | Call::Enable(..)
+            | Call::Disable(..) | Call::DepthFunc(..) | Call::BlendFunc(..) | Call::ClipControl(..) => {
+                match self.fixed.iter_mut().find(|c| same_slot(c, call)) {
+                    Some(slot) => *slot = call.clone(),
+                    None => self.fixed.push(call.clone()),
+                }
+            }
+            Call::GetUniformLocation(_, _, Some(location)) => {
+                self.lookups.insert(location.0, call.clone());
+            }
+            Call::BufferData(target, ..) => {
+                if let Some(buffer) = self.bound.get(target) {
+                    self.uploads.insert(buffer.0, (*target, vec![call.clone()]));
+                }
+            }
+            Call::BufferSubData(target, offset, data) => {
+                if let Some(buffer) = self.bound.get(target) {
+                    let (_, uploads) = self.uploads.entry(buffer.0).or_insert((*target, Vec::new()));
+                    // Drop earlier partial uploads the new one covers.
+                    let range = *offset as usize..*offset as usize + data.len();
+                    uploads.retain(|c| match c {
+                        Call::BufferSubData(_, o, d) => {
+                            (*o as usize) < range.start || *o as usize + d.len() > range.end
+                        }
+                        _ => true,
+                    });
+                    uploads.push(call.clone());
+                }
+            }
+            Call::Uniform1i(Some(location), ..)
+            | Call::UniformMatrix4fv(Some(location), ..)
+            | Call::Uniform(Some(location), ..) => {
+                if let Some(program) = self.program {
+                    self.uniforms.insert((program.0, location.0), call.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Calls that bring a backend replaying the sealed setup up to this
+    /// state, leaving the bindings as they are now.
+    fn calls(&self) -> Vec<Call> {
+        let mut calls: Vec<Call> = self.lookups.values().cloned().collect();
+        calls.extend(self.fixed.iter().cloned());
+        if !self.uploads.is_empty() {
+            calls.push(Call::BindVertexArray(None));
+            for (buffer, (target, uploads)) in &self.uploads {
+                calls.push(Call::BindBuffer(*target, Some(Buffer(*buffer))));
+                calls.extend(uploads.iter().cloned());
+            }
+            for (target, buffer) in &self.bound {
+                calls.push(Call::BindBuffer(*target, Some(*buffer)));
+            }
+            calls.push(Call::BindVertexArray(self.vao));
+        }
+        if !self.uniforms.is_empty() {
+            let mut program = None;
+            for ((p, _), call) in &self.uniforms {
+                if program != Some(*p) {
+                    program = Some(*p);
+                    calls.push(Call::UseProgram(Some(Program(*p))));
+                }
+                calls.push(call.clone());
+            }
+            calls.push(Call::UseProgram(self.program));
+        }
+        calls
+    }
+}
+
+impl Recorder {
+    pub fn wrap(inner: Rc<dyn Backend>) -> Self {
+        Self {
+            inner,
+            setup: RefCell::new(Some(Vec::new())),
+            sealed: RefCell::new(Vec::new()),
+            state: RefCell::new(State::default()),
+            prelude: RefCell::new(Vec::new()),
+            frame: RefCell::new(None),
+        }
+    }
+
+    /// Ends the setup phase. Objects created afterwards and outside of a
+    /// capture are unknown to traces.
+    pub fn seal(&self) {
+        if let Some(setup) = self.setup.take() {
+            *self.sealed.borrow_mut() = setup;
+        }
+    }
+
+    pub fn start(&self) {
+        *self.prelude.borrow_mut() = self.state.borrow().calls();
+        *self.frame.borrow_mut() = Some(Vec::new());
+    }
+
+    pub fn stop(&self) -> Trace {
+        Trace {
+            setup: [self.sealed.borrow().clone(), self.prelude.take()].concat(),
+            frame: self.frame.take().unwrap_or_default(),
+        }
+    }
+
+    fn record(&self, call: Call) {
+        let sealed = self.setup.borrow().is_none();
+        self.state.borrow_mut().update(&call, sealed);
+        if let Some(setup) = self.setup.borrow_mut().as_mut() {
+            setup.push(call);
+        } else if let Some(frame) = self.frame.borrow_mut().as_mut() {
+            frame.push(call);
+        }
+    }
+
+    fn recording(&self) -> bool {
+        self.setup.borrow().is_some() || self.frame.borrow().is_some()
+    }
+}
+
+impl Backend for Recorder {
+    fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.record(Call::Viewport(x, y, width, height));
+        self.inner.viewport(x, y, width, height);
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.record(Call::ClearColor(r, g, b, a));
+        self.inner.clear_color(r, g, b, a);
+    }
+
+    fn clear_depth(&self, depth: f32) {
+        self.record(Call::ClearDepth(depth));
+        self.inner.clear_depth(depth);
+    }
+
+    fn clear(&self, mask: u32) {
+        self.record(Call::Clear(mask));
+        self.inner.clear(mask);
+    }
+
+    fn enable(&self, cap: u32) {
+        self.record(Call::Enable(cap));
+        self.inner.enable(cap);
+    }
+
+    fn disable(&self, cap: u32) {
+        self.record(Call::Disable(cap));
+        self.inner.disable(cap);
+    }
+
+    fn depth_func(&self, func: u32) {
+        self.record(Call::DepthFunc(func));
+        self.inner.depth_func(func);
+    }
+
+    fn blend_func(&self, sfactor: u32, dfactor: u32) {
+        self.record(Call::BlendFunc(sfactor, dfactor));
+        self.inner.blend_func(sfactor, dfactor);
+    }
+
+    fn clip_control(&self, origin: u32, depth: u32) -> bool {
+        let applied = self.inner.clip_control(origin, depth);
+        if applied {
+            self.record(Call::ClipControl(origin, depth));
+        }
+        applied
+    }
+
+    fn create_buffer(&self) -> Option<Buffer> {
+        let buffer = self.inner.create_buffer()?;
+        self.record(Call::CreateBuffer(buffer));
+        Some(buffer)
+    }
+
+    fn delete_buffer(&self, buffer: Option<&Buffer>) {
+        self.record(Call::DeleteBuffer(buffer.copied()));
+        self.inner.delete_buffer(buffer);
+    }
+
+    fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+        self.record(Call::BindBuffer(target, buffer.copied()));
+        self.inner.bind_buffer(target, buffer);
+    }
+
+    fn buffer_data(&self, target: u32, data: &[u8], usage: u32) {
+        self.record(Call::BufferData(target, data.to_vec(), usage));
+        self.inner.buffer_data(target, data, usage);
+    }
+
+    fn buffer_sub_data(&self, target: u32, offset: i32, data: &[u8]) {
+        self.record(Call::BufferSubData(target, offset, data.to_vec()));
+        self.inner.buffer_sub_data(target, offset, data);
+    }
+
+    fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<&Buffer>) {
+        self.record(Call::BindBufferBase(target, index, buffer.copied()));
+        self.inner.bind_buffer_base(target, index, buffer);
+    }
+
+    fn create_vertex_array(&self) -> Option<VertexArray> {
+        let vao = self.inner.create_vertex_array()?;
+        self.record(Call::CreateVertexArray(vao));
+        Some(vao)
+    }
+
+    fn delete_vertex_array(&self, vao: Option<&VertexArray>) {
+        self.record(Call::DeleteVertexArray(vao.copied()));
+        self.inner.delete_vertex_array(vao);
+    }
+
+    fn bind_vertex_array(&self, vao: Option<&VertexArray>) {
+        self.record(Call::BindVertexArray(vao.copied()));
+        self.inner.bind_vertex_array(vao);
+    }
+
+    fn enable_vertex_attrib_array(&self, index: u32) {
+        self.record(Call::EnableVertexAttribArray(index));
+        self.inner.enable_vertex_attrib_array(index);
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: u32,
+        size: i32,
+        type_: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    ) {
+        self.record(Call::VertexAttribPointer(index, size, type_, normalized, stride, offset));
+        self.inner.vertex_attrib_pointer(index, size, type_, normalized, stride, offset);
+    }
+
+    fn create_shader(&self, type_: u32) -> Option<Shader> {
+        let shader = self.inner.create_shader(type_)?;
+        self.record(Call::CreateShader(type_, shader));
+        Some(shader)
+    }
+
+    fn shader_source(&self, shader: &Shader, source: &str) {
+        self.record(Call::ShaderSource(*shader, source.to_owned()));
+        self.inner.shader_source(shader, source);
+    }
+
+    fn compile_shader(&self, shader: &Shader) {
+        self.record(Call::CompileShader(*shader));
+        self.inner.compile_shader(shader);
+    }
+
+    fn shader_compile_status(&self, shader: &Shader) -> bool {
+        self.inner.shader_compile_status(shader)
+    }
+
+    fn shader_info_log(&self, shader: &Shader) -> Option<String> {
+        self.inner.shader_info_log(shader)
+    }
+
+    fn delete_shader(&self, shader: Option<&Shader>) {
+        self.record(Call::DeleteShader(shader.copied()));
+        self.inner.delete_shader(shader);
+    }
+
+    fn create_program(&self) -> Option<Program> {
+        let program = self.inner.create_program()?;
+        self.record(Call::CreateProgram(program));
+        Some(program)
+    }
+
+    fn attach_shader(&self, program: &Program, shader: &Shader) {
+        self.record(Call::AttachShader(*program, *shader));
+        self.inner.attach_shader(program, shader);
+    }
+
+    fn link_program(&self, program: &Program) {
+        self.record(Call::LinkProgram(*program));
+        self.inner.link_program(program);
+    }
+
+    fn program_link_status(&self, program: &Program) -> bool {
+        self.inner.program_link_status(program)
+    }
+
+    fn program_info_log(&self, program: &Program) -> Option<String> {
+        self.inner.program_info_log(program)
+    }
+
+    fn delete_program(&self, program: Option<&Program>) {
+        self.record(Call::DeleteProgram(program.copied()));
+        self.inner.delete_program(program);
+    }
+
+    fn use_program(&self, program: Option<&Program>) {
+        self.record(Call::UseProgram(program.copied()));
+        self.inner.use_program(program);
+    }
+
+    fn bind_attrib_location(&self, program: &Program, index: u32, name: &str) {
+        self.record(Call::BindAttribLocation(*program, index, name.to_owned()));
+        self.inner.bind_attrib_location(program, index, name);
+    }
+
+    fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+        let location = self.inner.get_uniform_location(program, name);
+        self.record(Call::GetUniformLocation(*program, name.to_owned(), location));
+        location
+    }
+
+    fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+        self.inner.get_attrib_location(program, name)
+    }
+
+    fn active_uniforms(&self, program: &Program) -> Vec<ActiveInfo> {
+        self.inner.active_uniforms(program)
+    }
+
+    fn active_attributes(&self, program: &Program) -> Vec<ActiveInfo> {
+        self.inner.active_attributes(program)
+    }
+
+    fn active_uniform_blocks(&self, program: &Program) -> Vec<String> {
+        self.inner.active_uniform_blocks(program)
+    }
+
+    fn uniform_block_binding(&self, program: &Program, block_index: u32, binding: u32) {
+        self.record(Call::UniformBlockBinding(*program, block_index, binding));
+        self.inner.uniform_block_binding(program, block_index, binding);
+    }
+
+    fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+        self.record(Call::Uniform1i(location.copied(), x));
+        self.inner.uniform1i(location, x);
+    }
+
+    fn uniform_matrix4fv(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]) {
+        self.record(Call::UniformMatrix4fv(location.copied(), transpose, data.to_vec()));
+        self.inner.uniform_matrix4fv(location, transpose, data);
+    }
+
+    fn uniform(&self, location: Option<&UniformLocation>, value: &UniformValue) {
+        self.record(Call::Uniform(location.copied(), value.clone()));
+        self.inner.uniform(location, value);
+    }
+
+    fn create_texture(&self) -> Option<Texture> {
+        let texture = self.inner.create_texture()?;
+        self.record(Call::CreateTexture(texture));
+        Some(texture)
+    }
+
+    fn delete_texture(&self, texture: Option<&Texture>) {
+        self.record(Call::DeleteTexture(texture.copied()));
+        self.inner.delete_texture(texture);
+    }
+
+    fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+        self.record(Call::BindTexture(target, texture.copied()));
+        self.inner.bind_texture(target, texture);
+    }
+
+    fn active_texture(&self, unit: u32) {
+        self.record(Call::ActiveTexture(unit));
+        self.inner.active_texture(unit);
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        type_: u32,
+        pixels: Option<&[u8]>,
+    ) {
+        if self.recording() {
+            self.record(Call::TexImage2d {
+                target,
+                level,
+                internal_format,
+                width,
+                height,
+                format,
+                type_,
+                pixels: pixels.map(|p| p.to_vec()),
+            });
+        }
+        self.inner.tex_image_2d(target, level, internal_format, width, height, format, type_, pixels);
+    }
+
+    fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+        self.record(Call::TexParameteri(target, pname, param));
+        self.inner.tex_parameteri(target, pname, param);
+    }
+
+    fn generate_mipmap(&self, target: u32) {
+        self.record(Call::GenerateMipmap(target));
+        self.inner.generate_mipmap(target);
+    }
+
+    fn load_image(&self, texture: &Texture, url: &str) {
+        self.record(Call::LoadImage(*texture, url.to_owned()));
+        self.inner.load_image(texture, url);
+    }
+
+    fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+        self.record(Call::DrawArrays(mode, first, count));
+        self.inner.draw_arrays(mode, first, count);
+    }
+
+    fn draw_elements(&self, mode: u32, count: i32, type_: u32, offset: i32) {
+        self.record(Call::DrawElements(mode, count, type_, offset));
+        self.inner.draw_elements(mode, count, type_, offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::Headless;
+    use crate::camera::Camera;
+    use crate::engine::Engine;
+    use crate::glm::Color;
+    use super::*;
+
+    /// Replays `trace` into a fresh backend and returns what it issued.
+    fn replayed(trace: &Trace) -> Vec<Call> {
+        let headless = Headless::default();
+        trace.replay(&headless);
+        headless.take_calls()
+    }
+
+    #[test]
+    fn state_set_after_seal_reaches_the_trace() {
+        let mut engine = Engine::create(Rc::new(Headless::default()));
+        engine.setup();
+        engine.set_clear_color(&Color::rgb(1.0, 0.0, 0.0));
+        engine.set_clear_color(&Color::rgb(0.0, 1.0, 0.0));
+        engine.set_camera(Camera { reverse_z: true, ..Camera::default() });
+        let calls = replayed(&engine.capture_frame());
+        let depth = calls.iter().rev().find(|c| matches!(c, Call::DepthFunc(..)));
+        assert_eq!(depth, Some(&Call::DepthFunc(WebGl::GREATER)));
+        assert!(calls.contains(&Call::ClearDepth(0.0)));
+        let clear = calls.iter().position(|c| matches!(c, Call::Clear(..))).unwrap();
+        let color = calls[..clear].iter().rev().find(|c| matches!(c, Call::ClearColor(..)));
+        assert_eq!(color, Some(&Call::ClearColor(0.0, 1.0, 0.0, 1.0)));
+        // Later captures keep it, as nothing has changed it since.
+        let calls = replayed(&engine.capture_frame());
+        let color = calls.iter().rev().find(|c| matches!(c, Call::ClearColor(..)));
+        assert_eq!(color, Some(&Call::ClearColor(0.0, 1.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn uploads_and_uniforms_after_seal_are_replayed() {
+        let recorder = Recorder::wrap(Rc::new(Headless::default()));
+        let buffer = recorder.create_buffer().unwrap();
+        recorder.bind_buffer(WebGl::ARRAY_BUFFER, Some(&buffer));
+        recorder.buffer_data(WebGl::ARRAY_BUFFER, &[0; 8], WebGl::DYNAMIC_DRAW);
+        let program = recorder.create_program().unwrap();
+        for (type_, source) in [
+            (WebGl::VERTEX_SHADER, "#version 300 es\nuniform int tint;\nvoid main() {}\n"),
+            (WebGl::FRAGMENT_SHADER, "#version 300 es\nvoid main() {}\n"),
+        ] {
+            let shader = recorder.create_shader(type_).unwrap();
+            recorder.shader_source(&shader, source);
+            recorder.compile_shader(&shader);
+            recorder.attach_shader(&program, &shader);
+        }
+        recorder.link_program(&program);
+        recorder.use_program(Some(&program));
+        recorder.seal();
+        //
+        let location = recorder.get_uniform_location(&program, "tint");
+        assert!(location.is_some());
+        recorder.uniform1i(location.as_ref(), 1);
+        recorder.uniform1i(location.as_ref(), 2);
+        recorder.buffer_sub_data(WebGl::ARRAY_BUFFER, 4, &[1; 4]);
+        recorder.buffer_sub_data(WebGl::ARRAY_BUFFER, 0, &[2; 8]);
+        recorder.buffer_sub_data(WebGl::ARRAY_BUFFER, 0, &[3; 4]);
+        recorder.start();
+        recorder.draw_arrays(WebGl::TRIANGLES, 0, 3);
+        let trace = recorder.stop();
+        let uploads: Vec<&Call> = trace.setup.iter()
+            .filter(|c| matches!(c, Call::BufferSubData(..)))
+            .collect();
+        assert_eq!(uploads, [
+            &Call::BufferSubData(WebGl::ARRAY_BUFFER, 0, vec![2; 8]),
+            &Call::BufferSubData(WebGl::ARRAY_BUFFER, 0, vec![3; 4]),
+        ]);
+        let uniforms: Vec<&Call> = trace.setup.iter()
+            .filter(|c| matches!(c, Call::Uniform1i(..)))
+            .collect();
+        assert_eq!(uniforms, [&Call::Uniform1i(location, 2)]);
+        assert_eq!(trace.frame, [Call::DrawArrays(WebGl::TRIANGLES, 0, 3)]);
+        // The replay resolves the late lookup and binds what was bound.
+        let calls = replayed(&trace);
+        assert!(calls.contains(&Call::GetUniformLocation(program, "tint".to_owned(), location)));
+        let last_bind = calls.iter().rev().find(|c| matches!(c, Call::BindBuffer(..)));
+        assert_eq!(last_bind, Some(&Call::BindBuffer(WebGl::ARRAY_BUFFER, Some(buffer))));
+        assert_eq!(calls[calls.len() - 2], Call::UseProgram(Some(program)));
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use super::{Backend, Buffer, Call, Program, Shader, Texture, UniformLocation, VertexArray};
+
+/// GL calls of one captured frame, preceded by the setup calls that
+/// created the objects the frame uses.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace {
+    pub setup: Vec<Call>,
+    pub frame: Vec<Call>,
+}
+
+impl Trace {
+    /// One call per line, setup first, for diffing two captures.
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        for (title, calls) in [("setup", &self.setup), ("frame", &self.frame)] {
+            writeln!(out, "# {} ({} calls)", title, calls.len()).unwrap();
+            for call in calls {
+                writeln!(out, "{}", call).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Re-issues the setup and frame calls, objects are created anew on
+    /// `backend` and every later reference is remapped to them.
+    pub fn replay(&self, backend: &dyn Backend) {
+        let mut replayer = Replayer { backend, ids: HashMap::new() };
+        for call in self.setup.iter().chain(self.frame.iter()) {
+            replayer.issue(call);
+        }
+    }
+}
+
+struct Replayer<'a> {
+    backend: &'a dyn Backend,
+    ids: HashMap<u32, u32>,
+}
+
+impl Replayer<'_> {
+    /// Objects the trace did not create keep their recorded id.
+    fn id(&self, id: u32) -> u32 {
+        *self.ids.get(&id).unwrap_or(&id)
+    }
+
+    fn map(&mut self, old: u32, new: Option<u32>) {
+        if let Some(new) = new {
+            self.ids.insert(old, new);
+        }
+    }
+
+    fn issue(&mut self, call: &Call) {
+        let gl = self.backend;
+        let buf = |b: &Option<Buffer>| b.map(|b| Buffer(self.id(b.0)));
+        let vao = |v: &Option<VertexArray>| v.map(|v| VertexArray(self.id(v.0)));
+        let sh = |s: &Shader| Shader(self.id(s.0));
+        let pro = |p: &Program| Program(self.id(p.0));
+        let tex = |t: &Option<Texture>| t.map(|t| Texture(self.id(t.0)));
+        let loc = |l: &Option<UniformLocation>| l.map(|l| UniformLocation(self.id(l.0)));
+        match call {
+            Call::Viewport(x, y, w, h) => gl.viewport(*x, *y, *w, *h),
+            Call::ClearColor(r, g, b, a) => gl.clear_color(*r, *g, *b, *a),
+            Call::ClearDepth(d) => gl.clear_depth(*d),
+            Call::Clear(mask) => gl.clear(*mask),
+            Call::Enable(cap) => gl.enable(*cap),
+            Call::Disable(cap) => gl.disable(*cap),
+            Call::DepthFunc(func) => gl.depth_func(*func),
+            Call::BlendFunc(s, d) => gl.blend_func(*s, *d),
+            Call::ClipControl(o, d) => {
+                gl.clip_control(*o, *d);
+            }
+            Call::CreateBuffer(b) => {
+                let new = gl.create_buffer().map(|n| n.0);
+                self.map(b.0, new);
+            }
+            Call::DeleteBuffer(b) => gl.delete_buffer(buf(b).as_ref()),
+            Call::BindBuffer(target, b) => gl.bind_buffer(*target, buf(b).as_ref()),
+            Call::BufferData(target, data, usage) => gl.buffer_data(*target, data, *usage),
+            Call::BufferSubData(target, offset, data) => gl.buffer_sub_data(*target, *offset, data),
+            Call::BindBufferBase(target, index, b) => gl.bind_buffer_base(*target, *index, buf(b).as_ref()),
+            Call::CreateVertexArray(v) => {
+                let new = gl.create_vertex_array().map(|n| n.0);
+                self.map(v.0, new);
+            }
+            Call::DeleteVertexArray(v) => gl.delete_vertex_array(vao(v).as_ref()),
+            Call::BindVertexArray(v) => gl.bind_vertex_array(vao(v).as_ref()),
+            Call::EnableVertexAttribArray(index) => gl.enable_vertex_attrib_array(*index),
+            Call::VertexAttribPointer(index, size, type_, normalized, stride, offset) => {
+                gl.vertex_attrib_pointer(*index, *size, *type_, *normalized, *stride, *offset)
+            }
+            Call::CreateShader(type_, s) => {
+                let new = gl.create_shader(*type_).map(|n| n.0);
+                self.map(s.0, new);
+            }
+            Call::ShaderSource(s, source) => gl.shader_source(&sh(s), source),
+            Call::CompileShader(s) => gl.compile_shader(&sh(s)),
+            Call::DeleteShader(s) => gl.delete_shader(s.as_ref().map(sh).as_ref()),
+            Call::CreateProgram(p) => {
+                let new = gl.create_program().map(|n| n.0);
+                self.map(p.0, new);
+            }
+            Call::AttachShader(p, s) => gl.attach_shader(&pro(p), &sh(s)),
+            Call::LinkProgram(p) => gl.link_program(&pro(p)),
+            Call::DeleteProgram(p) => gl.delete_program(p.as_ref().map(pro).as_ref()),
+            Call::UseProgram(p) => gl.use_program(p.as_ref().map(pro).as_ref()),
+            Call::BindAttribLocation(p, index, name) => gl.bind_attrib_location(&pro(p), *index, name),
+            Call::UniformBlockBinding(p, index, binding) => gl.uniform_block_binding(&pro(p), *index, *binding),
+            Call::GetUniformLocation(p, name, l) => {
+                let new = gl.get_uniform_location(&pro(p), name).map(|n| n.0);
+                if let Some(l) = l {
+                    self.map(l.0, new);
+                }
+            }
+            Call::Uniform1i(l, x) => gl.uniform1i(loc(l).as_ref(), *x),
+            Call::UniformMatrix4fv(l, transpose, data) => {
+                gl.uniform_matrix4fv(loc(l).as_ref(), *transpose, data)
+            }
+            Call::Uniform(l, value) => gl.uniform(loc(l).as_ref(), value),
+            Call::CreateTexture(t) => {
+                let new = gl.create_texture().map(|n| n.0);
+                self.map(t.0, new);
+            }
+            Call::DeleteTexture(t) => gl.delete_texture(tex(t).as_ref()),
+            Call::BindTexture(target, t) => gl.bind_texture(*target, tex(t).as_ref()),
+            Call::ActiveTexture(unit) => gl.active_texture(*unit),
+            Call::TexImage2d { target, level, internal_format, width, height, format, type_, pixels } => {
+                gl.tex_image_2d(
+                    *target, *level, *internal_format,
+                    *width, *height,
+                    *format, *type_, pixels.as_deref(),
+                )
+            }
+            Call::TexParameteri(target, pname, param) => gl.tex_parameteri(*target, *pname, *param),
+            Call::GenerateMipmap(target) => gl.generate_mipmap(*target),
+            Call::LoadImage(t, url) => gl.load_image(&Texture(self.id(t.0)), url),
+            Call::DrawArrays(mode, first, count) => gl.draw_arrays(*mode, *first, *count),
+            Call::DrawElements(mode, count, type_, offset) => gl.draw_elements(*mode, *count, *type_, *offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use crate::backend::Headless;
+    use crate::engine::Engine;
+    use super::*;
+
+    fn capture() -> Trace {
+        let mut engine = Engine::create(Rc::new(Headless::default()));
+        engine.setup();
+        engine.capture_frame()
+    }
+
+    #[test]
+    fn replay_reissues_the_recorded_calls() {
+        let trace = capture();
+        assert!(trace.frame.iter().any(|c| matches!(c, Call::DrawElements(..))));
+        let headless = Headless::default();
+        trace.replay(&headless);
+        assert_eq!(headless.take_calls(), [trace.setup.clone(), trace.frame.clone()].concat());
+        assert!(trace.dump().starts_with(&format!("# setup ({} calls)\n", trace.setup.len())));
+    }
+
+    #[test]
+    fn replay_remaps_objects() {
+        let trace = capture();
+        let headless = Headless::default();
+        trace.replay(&headless);
+        trace.replay(&headless);
+        let calls = headless.take_calls();
+        let (first, second) = calls.split_at(calls.len() / 2);
+        assert_eq!(first.len(), second.len());
+        let vao = |calls: &[Call]| calls.iter().rev().find_map(|c| match c {
+            Call::CreateVertexArray(v) => Some(*v),
+            _ => None,
+        });
+        let bound = |calls: &[Call]| calls.iter().rev().find_map(|c| match c {
+            Call::BindVertexArray(Some(v)) => Some(*v),
+            _ => None,
+        });
+        // The second run draws with its own objects, not the first run's.
+        assert_ne!(vao(first), vao(second));
+        assert_eq!(bound(second), vao(second));
+    }
+}
@@ -0,0 +1,180 @@
+use web_sys::WebGl2RenderingContext as WebGl;
+
+/// A value for a single uniform, the variant decides the `uniform*` call.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UniformValue {
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Int(i32),
+    IVec2([i32; 2]),
+    IVec3([i32; 3]),
+    IVec4([i32; 4]),
+    UInt(u32),
+    Bool(bool),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+}
+
+impl UniformValue {
+    /// The GL type enum of the matching GLSL declaration.
+    pub fn gl_type(&self) -> u32 {
+        match self {
+            UniformValue::Float(_) => WebGl::FLOAT,
+            UniformValue::Vec2(_) => WebGl::FLOAT_VEC2,
+            UniformValue::Vec3(_) => WebGl::FLOAT_VEC3,
+            UniformValue::Vec4(_) => WebGl::FLOAT_VEC4,
+            UniformValue::Int(_) => WebGl::INT,
+            UniformValue::IVec2(_) => WebGl::INT_VEC2,
+            UniformValue::IVec3(_) => WebGl::INT_VEC3,
+            UniformValue::IVec4(_) => WebGl::INT_VEC4,
+            UniformValue::UInt(_) => WebGl::UNSIGNED_INT,
+            UniformValue::Bool(_) => WebGl::BOOL,
+            UniformValue::Mat3(_) => WebGl::FLOAT_MAT3,
+            UniformValue::Mat4(_) => WebGl::FLOAT_MAT4,
+        }
+    }
+
+    /// Whether this value can be assigned to a uniform of `gl_type`,
+    /// samplers and bools also take an `Int`.
+    pub fn fits(&self, gl_type: u32) -> bool {
+        gl_type == self.gl_type() || match self {
+            UniformValue::Int(_) => gl_type == WebGl::BOOL || is_sampler(gl_type),
+            _ => false,
+        }
+    }
+}
+
+pub fn is_sampler(gl_type: u32) -> bool {
+    matches!(
+        gl_type,
+        WebGl::SAMPLER_2D | WebGl::SAMPLER_3D | WebGl::SAMPLER_CUBE
+            | WebGl::SAMPLER_2D_SHADOW | WebGl::SAMPLER_2D_ARRAY
+            | WebGl::SAMPLER_2D_ARRAY_SHADOW | WebGl::SAMPLER_CUBE_SHADOW
+            | WebGl::INT_SAMPLER_2D | WebGl::INT_SAMPLER_3D | WebGl::INT_SAMPLER_CUBE
+            | WebGl::INT_SAMPLER_2D_ARRAY | WebGl::UNSIGNED_INT_SAMPLER_2D
+            | WebGl::UNSIGNED_INT_SAMPLER_3D | WebGl::UNSIGNED_INT_SAMPLER_CUBE
+            | WebGl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+    )
+}
+
+/// GLSL spelling of a GL type enum, for messages.
+pub fn type_name(gl_type: u32) -> &'static str {
+    match gl_type {
+        WebGl::FLOAT => "float",
+        WebGl::FLOAT_VEC2 => "vec2",
+        WebGl::FLOAT_VEC3 => "vec3",
+        WebGl::FLOAT_VEC4 => "vec4",
+        WebGl::INT => "int",
+        WebGl::INT_VEC2 => "ivec2",
+        WebGl::INT_VEC3 => "ivec3",
+        WebGl::INT_VEC4 => "ivec4",
+        WebGl::UNSIGNED_INT => "uint",
+        WebGl::UNSIGNED_INT_VEC2 => "uvec2",
+        WebGl::UNSIGNED_INT_VEC3 => "uvec3",
+        WebGl::UNSIGNED_INT_VEC4 => "uvec4",
+        WebGl::BOOL => "bool",
+        WebGl::BOOL_VEC2 => "bvec2",
+        WebGl::BOOL_VEC3 => "bvec3",
+        WebGl::BOOL_VEC4 => "bvec4",
+        WebGl::FLOAT_MAT2 => "mat2",
+        WebGl::FLOAT_MAT3 => "mat3",
+        WebGl::FLOAT_MAT4 => "mat4",
+        WebGl::SAMPLER_2D => "sampler2D",
+        WebGl::SAMPLER_3D => "sampler3D",
+        WebGl::SAMPLER_CUBE => "samplerCube",
+        WebGl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        WebGl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        WebGl::SAMPLER_2D_ARRAY_SHADOW => "sampler2DArrayShadow",
+        WebGl::SAMPLER_CUBE_SHADOW => "samplerCubeShadow",
+        WebGl::INT_SAMPLER_2D => "isampler2D",
+        WebGl::INT_SAMPLER_3D => "isampler3D",
+        WebGl::INT_SAMPLER_CUBE => "isamplerCube",
+        WebGl::INT_SAMPLER_2D_ARRAY => "isampler2DArray",
+        WebGl::UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        WebGl::UNSIGNED_INT_SAMPLER_3D => "usampler3D",
+        WebGl::UNSIGNED_INT_SAMPLER_CUBE => "usamplerCube",
+        WebGl::UNSIGNED_INT_SAMPLER_2D_ARRAY => "usampler2DArray",
+        _ => "unknown",
+    }
+}
+
+/// Inverse of `type_name`.
+pub fn type_from_name(name: &str) -> Option<u32> {
+    const TYPES: &[u32] = &[
+        WebGl::FLOAT, WebGl::FLOAT_VEC2, WebGl::FLOAT_VEC3, WebGl::FLOAT_VEC4,
+        WebGl::INT, WebGl::INT_VEC2, WebGl::INT_VEC3, WebGl::INT_VEC4,
+        WebGl::UNSIGNED_INT, WebGl::UNSIGNED_INT_VEC2, WebGl::UNSIGNED_INT_VEC3,
+        WebGl::UNSIGNED_INT_VEC4, WebGl::BOOL, WebGl::BOOL_VEC2, WebGl::BOOL_VEC3,
+        WebGl::BOOL_VEC4, WebGl::FLOAT_MAT2, WebGl::FLOAT_MAT3, WebGl::FLOAT_MAT4,
+        WebGl::SAMPLER_2D, WebGl::SAMPLER_3D, WebGl::SAMPLER_CUBE,
+        WebGl::SAMPLER_2D_SHADOW, WebGl::SAMPLER_2D_ARRAY, WebGl::SAMPLER_2D_ARRAY_SHADOW,
+        WebGl::SAMPLER_CUBE_SHADOW, WebGl::INT_SAMPLER_2D, WebGl::INT_SAMPLER_3D,
+        WebGl::INT_SAMPLER_CUBE, WebGl::INT_SAMPLER_2D_ARRAY, WebGl::UNSIGNED_INT_SAMPLER_2D,
+        WebGl::UNSIGNED_INT_SAMPLER_3D, WebGl::UNSIGNED_INT_SAMPLER_CUBE,
+        WebGl::UNSIGNED_INT_SAMPLER_2D_ARRAY,
+    ];
+    TYPES.iter().copied().find(|t| type_name(*t) == name)
+}
+
+impl From<f32> for UniformValue {
+    fn from(v: f32) -> Self {
+        UniformValue::Float(v)
+    }
+}
+
+impl From<i32> for UniformValue {
+    fn from(v: i32) -> Self {
+        UniformValue::Int(v)
+    }
+}
+
+impl From<u32> for UniformValue {
+    fn from(v: u32) -> Self {
+        UniformValue::UInt(v)
+    }
+}
+
+impl From<bool> for UniformValue {
+    fn from(v: bool) -> Self {
+        UniformValue::Bool(v)
+    }
+}
+
+impl From<crate::glm::Vec2> for UniformValue {
+    fn from(v: crate::glm::Vec2) -> Self {
+        UniformValue::Vec2([v.x, v.y])
+    }
+}
+
+impl From<crate::glm::Vec3> for UniformValue {
+    fn from(v: crate::glm::Vec3) -> Self {
+        UniformValue::Vec3([v.x, v.y, v.z])
+    }
+}
+
+impl From<crate::glm::Vec4> for UniformValue {
+    fn from(v: crate::glm::Vec4) -> Self {
+        UniformValue::Vec4([v.x, v.y, v.z, v.w])
+    }
+}
+
+impl From<crate::glm::Color> for UniformValue {
+    fn from(c: crate::glm::Color) -> Self {
+        UniformValue::Vec4(c.into())
+    }
+}
+
+impl From<&crate::glm::Mat3> for UniformValue {
+    fn from(m: &crate::glm::Mat3) -> Self {
+        UniformValue::Mat3(m[..].try_into().unwrap())
+    }
+}
+
+impl From<&crate::glm::Mat4> for UniformValue {
+    fn from(m: &crate::glm::Mat4) -> Self {
+        UniformValue::Mat4(m.to_cols_array())
+    }
+}
@@ -0,0 +1,459 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlImageElement};
+use web_sys::WebGl2RenderingContext as WebGl;
+use web_sys::{WebGlActiveInfo, WebGlBuffer, WebGlProgram, WebGlShader, WebGlTexture};
+use web_sys::{WebGlUniformLocation, WebGlVertexArrayObject};
+use super::{ActiveInfo, Backend, Buffer, Program, Shader, Texture, UniformLocation};
+use super::{UniformValue, VertexArray};
+
+/// Maps backend handles to the JS objects they stand for.
+struct Registry<T> {
+    items: RefCell<HashMap<u32, T>>,
+}
+
+impl<T: Clone> Registry<T> {
+    fn new() -> Self {
+        Self { items: RefCell::new(HashMap::new()) }
+    }
+
+    fn insert(&self, id: u32, item: T) {
+        self.items.borrow_mut().insert(id, item);
+    }
+
+    fn get(&self, id: u32) -> Option<T> {
+        self.items.borrow().get(&id).cloned()
+    }
+
+    fn remove(&self, id: u32) -> Option<T> {
+        self.items.borrow_mut().remove(&id)
+    }
+}
+
+pub struct WebGlBackend {
+    gl: WebGl,
+    next: Cell<u32>,
+    buffers: Registry<WebGlBuffer>,
+    vaos: Registry<WebGlVertexArrayObject>,
+    shaders: Registry<WebGlShader>,
+    programs: Registry<WebGlProgram>,
+    textures: Registry<WebGlTexture>,
+    locations: Registry<WebGlUniformLocation>,
+    /// Location ids by program and uniform name, so repeated lookups reuse
+    /// one registry entry.
+    uniform_ids: RefCell<HashMap<(u32, String), u32>>,
+    clip_control: Option<js_sys::Object>,
+}
+
+impl WebGlBackend {
+    pub fn wrap(gl: WebGl) -> Self {
+        let clip_control = gl.get_extension("EXT_clip_control").ok().flatten();
+        Self {
+            gl,
+            next: Cell::new(1),
+            buffers: Registry::new(),
+            vaos: Registry::new(),
+            shaders: Registry::new(),
+            programs: Registry::new(),
+            textures: Registry::new(),
+            locations: Registry::new(),
+            uniform_ids: RefCell::new(HashMap::new()),
+            clip_control,
+        }
+    }
+
+    fn alloc(&self) -> u32 {
+        let id = self.next.get();
+        self.next.set(id + 1);
+        id
+    }
+
+    fn active(
+        &self,
+        program: &Program,
+        pname: u32,
+        get: impl Fn(&WebGlProgram, u32) -> Option<WebGlActiveInfo>,
+    ) -> Vec<ActiveInfo> {
+        let program = match self.programs.get(program.0) {
+            Some(program) => program,
+            None => return Vec::new(),
+        };
+        let count = self.gl.get_program_parameter(&program, pname).as_f64().unwrap_or(0.0) as u32;
+        (0..count)
+            .filter_map(|i| get(&program, i))
+            .map(|info| ActiveInfo { name: info.name(), type_: info.type_(), size: info.size() })
+            .collect()
+    }
+}
+
+impl Backend for WebGlBackend {
+    fn viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.gl.viewport(x, y, width, height);
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.gl.clear_color(r, g, b, a);
+    }
+
+    fn clear_depth(&self, depth: f32) {
+        self.gl.clear_depth(depth);
+    }
+
+    fn clear(&self, mask: u32) {
+        self.gl.clear(mask);
+    }
+
+    fn enable(&self, cap: u32) {
+        self.gl.enable(cap);
+    }
+
+    fn disable(&self, cap: u32) {
+        self.gl.disable(cap);
+    }
+
+    fn depth_func(&self, func: u32) {
+        self.gl.depth_func(func);
+    }
+
+    fn blend_func(&self, sfactor: u32, dfactor: u32) {
+        self.gl.blend_func(sfactor, dfactor);
+    }
+
+    fn clip_control(&self, origin: u32, depth: u32) -> bool {
+        let ext = match self.clip_control.as_ref() {
+            Some(ext) => ext,
+            None => return false,
+        };
+        js_sys::Reflect::get(ext, &"clipControlEXT".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok())
+            .is_some_and(|f| f.call2(ext, &origin.into(), &depth.into()).is_ok())
+    }
+
+    fn create_buffer(&self) -> Option<Buffer> {
+        let id = self.alloc();
+        self.buffers.insert(id, self.gl.create_buffer()?);
+        Some(Buffer(id))
+    }
+
+    fn delete_buffer(&self, buffer: Option<&Buffer>) {
+        let buffer = buffer.and_then(|b| self.buffers.remove(b.0));
+        self.gl.delete_buffer(buffer.as_ref());
+    }
+
+    fn bind_buffer(&self, target: u32, buffer: Option<&Buffer>) {
+        let buffer = buffer.and_then(|b| self.buffers.get(b.0));
+        self.gl.bind_buffer(target, buffer.as_ref());
+    }
+
+    fn buffer_data(&self, target: u32, data: &[u8], usage: u32) {
+        self.gl.buffer_data_with_u8_array(target, data, usage);
+    }
+
+    fn buffer_sub_data(&self, target: u32, offset: i32, data: &[u8]) {
+        self.gl.buffer_sub_data_with_i32_and_u8_array(target, offset, data);
+    }
+
+    fn bind_buffer_base(&self, target: u32, index: u32, buffer: Option<&Buffer>) {
+        let buffer = buffer.and_then(|b| self.buffers.get(b.0));
+        self.gl.bind_buffer_base(target, index, buffer.as_ref());
+    }
+
+    fn create_vertex_array(&self) -> Option<VertexArray> {
+        let id = self.alloc();
+        self.vaos.insert(id, self.gl.create_vertex_array()?);
+        Some(VertexArray(id))
+    }
+
+    fn delete_vertex_array(&self, vao: Option<&VertexArray>) {
+        let vao = vao.and_then(|v| self.vaos.remove(v.0));
+        self.gl.delete_vertex_array(vao.as_ref());
+    }
+
+    fn bind_vertex_array(&self, vao: Option<&VertexArray>) {
+        let vao = vao.and_then(|v| self.vaos.get(v.0));
+        self.gl.bind_vertex_array(vao.as_ref());
+    }
+
+    fn enable_vertex_attrib_array(&self, index: u32) {
+        self.gl.enable_vertex_attrib_array(index);
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: u32,
+        size: i32,
+        type_: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    ) {
+        self.gl.vertex_attrib_pointer_with_i32(index, size, type_, normalized, stride, offset);
+    }
+
+    fn create_shader(&self, type_: u32) -> Option<Shader> {
+        let id = self.alloc();
+        self.shaders.insert(id, self.gl.create_shader(type_)?);
+        Some(Shader(id))
+    }
+
+    fn shader_source(&self, shader: &Shader, source: &str) {
+        if let Some(shader) = self.shaders.get(shader.0) {
+            self.gl.shader_source(&shader, source);
+        }
+    }
+
+    fn compile_shader(&self, shader: &Shader) {
+        if let Some(shader) = self.shaders.get(shader.0) {
+            self.gl.compile_shader(&shader);
+        }
+    }
+
+    fn shader_compile_status(&self, shader: &Shader) -> bool {
+        self.shaders.get(shader.0).is_some_and(|shader| {
+            self.gl.get_shader_parameter(&shader, WebGl::COMPILE_STATUS)
+                .as_bool().unwrap_or(false)
+        })
+    }
+
+    fn shader_info_log(&self, shader: &Shader) -> Option<String> {
+        self.gl.get_shader_info_log(&self.shaders.get(shader.0)?)
+    }
+
+    fn delete_shader(&self, shader: Option<&Shader>) {
+        let shader = shader.and_then(|s| self.shaders.remove(s.0));
+        self.gl.delete_shader(shader.as_ref());
+    }
+
+    fn create_program(&self) -> Option<Program> {
+        let id = self.alloc();
+        self.programs.insert(id, self.gl.create_program()?);
+        Some(Program(id))
+    }
+
+    fn attach_shader(&self, program: &Program, shader: &Shader) {
+        if let (Some(program), Some(shader)) = (
+            self.programs.get(program.0),
+            self.shaders.get(shader.0),
+        ) {
+            self.gl.attach_shader(&program, &shader);
+        }
+    }
+
+    fn link_program(&self, program: &Program) {
+        if let Some(program) = self.programs.get(program.0) {
+            self.gl.link_program(&program);
+        }
+    }
+
+    fn program_link_status(&self, program: &Program) -> bool {
+        self.programs.get(program.0).is_some_and(|program| {
+            self.gl.get_program_parameter(&program, WebGl::LINK_STATUS)
+                .as_bool().unwrap_or(false)
+        })
+    }
+
+    fn program_info_log(&self, program: &Program) -> Option<String> {
+        self.gl.get_program_info_log(&self.programs.get(program.0)?)
+    }
+
+    fn delete_program(&self, program: Option<&Program>) {
+        if let Some(program) = program {
+            self.uniform_ids.borrow_mut().retain(|(owner, _), id| {
+                let keep = *owner != program.0;
+                if !keep {
+                    self.locations.remove(*id);
+                }
+                keep
+            });
+        }
+        let program = program.and_then(|p| self.programs.remove(p.0));
+        self.gl.delete_program(program.as_ref());
+    }
+
+    fn use_program(&self, program: Option<&Program>) {
+        let program = program.and_then(|p| self.programs.get(p.0));
+        self.gl.use_program(program.as_ref());
+    }
+
+    fn bind_attrib_location(&self, program: &Program, index: u32, name: &str) {
+        if let Some(program) = self.programs.get(program.0) {
+            self.gl.bind_attrib_location(&program, index, name);
+        }
+    }
+
+    fn get_uniform_location(&self, program: &Program, name: &str) -> Option<UniformLocation> {
+        let key = (program.0, name.to_owned());
+        if let Some(id) = self.uniform_ids.borrow().get(&key) {
+            return Some(UniformLocation(*id));
+        }
+        let program = self.programs.get(program.0)?;
+        let location = self.gl.get_uniform_location(&program, name)?;
+        let id = self.alloc();
+        self.locations.insert(id, location);
+        self.uniform_ids.borrow_mut().insert(key, id);
+        Some(UniformLocation(id))
+    }
+
+    fn get_attrib_location(&self, program: &Program, name: &str) -> i32 {
+        match self.programs.get(program.0) {
+            Some(program) => self.gl.get_attrib_location(&program, name),
+            None => -1,
+        }
+    }
+
+    fn active_uniforms(&self, program: &Program) -> Vec<ActiveInfo> {
+        self.active(program, WebGl::ACTIVE_UNIFORMS, |p, i| self.gl.get_active_uniform(p, i))
+    }
+
+    fn active_attributes(&self, program: &Program) -> Vec<ActiveInfo> {
+        self.active(program, WebGl::ACTIVE_ATTRIBUTES, |p, i| self.gl.get_active_attrib(p, i))
+    }
+
+    fn active_uniform_blocks(&self, program: &Program) -> Vec<String> {
+        let program = match self.programs.get(program.0) {
+            Some(program) => program,
+            None => return Vec::new(),
+        };
+        let count = self.gl.get_program_parameter(&program, WebGl::ACTIVE_UNIFORM_BLOCKS)
+            .as_f64()
+            .unwrap_or(0.0) as u32;
+        (0..count)
+            .filter_map(|i| self.gl.get_active_uniform_block_name(&program, i))
+            .collect()
+    }
+
+    fn uniform_block_binding(&self, program: &Program, block_index: u32, binding: u32) {
+        if let Some(program) = self.programs.get(program.0) {
+            self.gl.uniform_block_binding(&program, block_index, binding);
+        }
+    }
+
+    fn uniform1i(&self, location: Option<&UniformLocation>, x: i32) {
+        let location = location.and_then(|l| self.locations.get(l.0));
+        self.gl.uniform1i(location.as_ref(), x);
+    }
+
+    fn uniform_matrix4fv(&self, location: Option<&UniformLocation>, transpose: bool, data: &[f32]) {
+        let location = location.and_then(|l| self.locations.get(l.0));
+        self.gl.uniform_matrix4fv_with_f32_array(location.as_ref(), transpose, data);
+    }
+
+    fn uniform(&self, location: Option<&UniformLocation>, value: &UniformValue) {
+        let location = location.and_then(|l| self.locations.get(l.0));
+        let l = location.as_ref();
+        match value {
+            UniformValue::Float(x) => self.gl.uniform1f(l, *x),
+            UniformValue::Vec2([x, y]) => self.gl.uniform2f(l, *x, *y),
+            UniformValue::Vec3([x, y, z]) => self.gl.uniform3f(l, *x, *y, *z),
+            UniformValue::Vec4([x, y, z, w]) => self.gl.uniform4f(l, *x, *y, *z, *w),
+            UniformValue::Int(x) => self.gl.uniform1i(l, *x),
+            UniformValue::IVec2([x, y]) => self.gl.uniform2i(l, *x, *y),
+            UniformValue::IVec3([x, y, z]) => self.gl.uniform3i(l, *x, *y, *z),
+            UniformValue::IVec4([x, y, z, w]) => self.gl.uniform4i(l, *x, *y, *z, *w),
+            UniformValue::UInt(x) => self.gl.uniform1ui(l, *x),
+            UniformValue::Bool(x) => self.gl.uniform1i(l, *x as i32),
+            UniformValue::Mat3(m) => self.gl.uniform_matrix3fv_with_f32_array(l, false, m),
+            UniformValue::Mat4(m) => self.gl.uniform_matrix4fv_with_f32_array(l, false, m),
+        }
+    }
+
+    fn create_texture(&self) -> Option<Texture> {
+        let id = self.alloc();
+        self.textures.insert(id, self.gl.create_texture()?);
+        Some(Texture(id))
+    }
+
+    fn delete_texture(&self, texture: Option<&Texture>) {
+        let texture = texture.and_then(|t| self.textures.remove(t.0));
+        self.gl.delete_texture(texture.as_ref());
+    }
+
+    fn bind_texture(&self, target: u32, texture: Option<&Texture>) {
+        let texture = texture.and_then(|t| self.textures.get(t.0));
+        self.gl.bind_texture(target, texture.as_ref());
+    }
+
+    fn active_texture(&self, unit: u32) {
+        self.gl.active_texture(unit);
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: u32,
+        level: i32,
+        internal_format: i32,
+        width: i32,
+        height: i32,
+        format: u32,
+        type_: u32,
+        pixels: Option<&[u8]>,
+    ) {
+        self.gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            target, level, internal_format,
+            width, height, 0,
+            format, type_, pixels,
+        ).unwrap();
+    }
+
+    fn tex_parameteri(&self, target: u32, pname: u32, param: i32) {
+        self.gl.tex_parameteri(target, pname, param);
+    }
+
+    fn generate_mipmap(&self, target: u32) {
+        self.gl.generate_mipmap(target);
+    }
+
+    fn load_image(&self, texture: &Texture, url: &str) {
+        let image = HtmlImageElement::new().unwrap();
+        let gl_ = self.gl.clone();
+        let texture_ = self.textures.get(texture.0);
+        let closure = Closure::wrap(Box::new(
+            move |event: Event| {
+                let image = event.target().unwrap()
+                    .dyn_into::<HtmlImageElement>()
+                    .unwrap();
+                gl_.bind_texture(WebGl::TEXTURE_2D, texture_.as_ref());
+                gl_.tex_image_2d_with_u32_and_u32_and_html_image_element(
+                    WebGl::TEXTURE_2D, 0,
+                    WebGl::RGBA as i32,
+                    WebGl::RGBA,
+                    WebGl::UNSIGNED_BYTE,
+                    &image,
+                ).unwrap();
+                //
+                gl_.generate_mipmap(WebGl::TEXTURE_2D);
+                gl_.tex_parameteri(
+                    WebGl::TEXTURE_2D,
+                    WebGl::TEXTURE_WRAP_S,
+                    WebGl::CLAMP_TO_EDGE as i32,
+                );
+                gl_.tex_parameteri(
+                    WebGl::TEXTURE_2D,
+                    WebGl::TEXTURE_WRAP_T,
+                    WebGl::CLAMP_TO_EDGE as i32,
+                );
+                gl_.tex_parameteri(
+                    WebGl::TEXTURE_2D,
+                    WebGl::TEXTURE_MIN_FILTER,
+                    WebGl::LINEAR as i32,
+                );
+            }
+        ) as Box<dyn FnMut(_)>);
+        image.add_event_listener_with_callback(
+            "load", closure.as_ref().unchecked_ref(),
+        ).unwrap();
+        image.set_src(url);
+        closure.forget();
+    }
+
+    fn draw_arrays(&self, mode: u32, first: i32, count: i32) {
+        self.gl.draw_arrays(mode, first, count);
+    }
+
+    fn draw_elements(&self, mode: u32, count: i32, type_: u32, offset: i32) {
+        self.gl.draw_elements_with_i32(mode, count, type_, offset);
+    }
+}
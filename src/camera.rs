@@ -0,0 +1,44 @@
+use crate::glm::{Mat4, Vec3};
+
+pub struct Camera {
+    /// Vertical field of view in radians.
+    pub fovy: f32,
+    pub aspect: f32,
+    pub near: f32,
+    /// `None` puts the far plane at infinity.
+    pub far: Option<f32>,
+    /// Maps near to depth 1 and far to 0, see `Engine::set_camera` for the
+    /// matching depth state. Only gains depth precision where
+    /// `EXT_clip_control` is available.
+    pub reverse_z: bool,
+    pub view: Mat4,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            fovy: 45f32.to_radians(),
+            aspect: 1.0,
+            near: 0.1,
+            far: Some(100.0),
+            reverse_z: false,
+            view: Mat4::from_translation(&Vec3::wrap(0.0, 0.0, -6.0)),
+        }
+    }
+}
+
+impl Camera {
+    /// `zero_to_one` picks the `[0, 1]` clip depth range of
+    /// `EXT_clip_control` for reverse-Z, other cameras ignore it.
+    pub fn projection(&self, zero_to_one: bool) -> Mat4 {
+        let (fovy, aspect, near) = (self.fovy, self.aspect, self.near);
+        match (self.far, self.reverse_z, zero_to_one) {
+            (Some(far), false, _) => Mat4::perspective_rh(fovy, aspect, near, far),
+            (Some(far), true, false) => Mat4::perspective_reverse_z_rh(fovy, aspect, near, far),
+            (Some(far), true, true) => Mat4::perspective_reverse_z_zo_rh(fovy, aspect, near, far),
+            (None, false, _) => Mat4::perspective_infinite_rh(fovy, aspect, near),
+            (None, true, false) => Mat4::perspective_infinite_reverse_z_rh(fovy, aspect, near),
+            (None, true, true) => Mat4::perspective_infinite_reverse_z_zo_rh(fovy, aspect, near),
+        }
+    }
+}
@@ -1,78 +1,266 @@
 use std::collections::LinkedList;
 use std::rc::Rc;
 use web_sys::WebGl2RenderingContext as WebGl;
-use web_sys::WebGlTexture;
-use crate::glm::{Mat4, Vec3};
+use crate::backend::{Backend, Recorder, Texture, Trace};
+use crate::backend::{LOWER_LEFT_EXT, NEGATIVE_ONE_TO_ONE_EXT, ZERO_TO_ONE_EXT};
+use crate::camera::Camera;
+use crate::glm::{self, Color, Mat4, Ray, Vec3, Vec4};
 use crate::obj::Quad;
+use crate::scene::Scene;
+use crate::shader::{FrameUniforms, ProgramCache, ShaderLibrary, UniformBuffer, UniformError};
 use crate::utils;
 
 pub trait Context {
-    fn gl(&self) -> &WebGl;
-    fn pro_matrix(&self) -> &[f32];
-    fn mod_matrix(&self) -> &[f32];
+    fn gl(&self) -> &Rc<dyn Backend>;
+    /// World matrix of the node being drawn, view and projection come
+    /// from the `Frame` uniform block.
+    fn mod_matrix(&self) -> &Mat4;
+    fn shaders(&self) -> &ShaderLibrary;
+    fn programs(&self) -> &ProgramCache;
+}
+
+/// Anything a scene node can draw, `Context::mod_matrix` holds the node's
+/// world matrix during `draw`.
+pub trait Renderable {
+    fn draw(&self, context: &dyn Context, texture: Option<&Texture>) -> Result<(), UniformError>;
 }
 
 pub struct Engine {
-    gl: Rc<WebGl>,
+    gl: Rc<dyn Backend>,
+    recorder: Rc<Recorder>,
     mod_mat: Mat4,
     pro_mat: Mat4,
+    camera: Camera,
+    viewport: Vec4,
     stamp: f64,
-    texture: Option<WebGlTexture>,
-    quad: Option<Quad>,
+    texture: Option<Texture>,
+    scene: Scene,
+    shaders: ShaderLibrary,
+    programs: ProgramCache,
+    frame: UniformBuffer,
 }
 
 impl Context for Engine {
-    fn gl(&self) -> &WebGl {
-        self.gl.as_ref()
+    fn gl(&self) -> &Rc<dyn Backend> {
+        &self.gl
     }
 
-    fn pro_matrix(&self) -> &[f32] {
-        &*self.pro_mat
+    fn mod_matrix(&self) -> &Mat4 {
+        &self.mod_mat
     }
 
-    fn mod_matrix(&self) -> &[f32] {
-        &*self.mod_mat
+    fn shaders(&self) -> &ShaderLibrary {
+        &self.shaders
+    }
+
+    fn programs(&self) -> &ProgramCache {
+        &self.programs
     }
 }
 
 impl Engine {
-    pub fn create(gl: WebGl) -> Self {
+    pub fn create(gl: Rc<dyn Backend>) -> Self {
+        let recorder = Rc::new(Recorder::wrap(gl));
+        let gl: Rc<dyn Backend> = recorder.clone();
+        let programs = ProgramCache::wrap(gl.clone());
+        programs.bind_block(FrameUniforms::BLOCK, FrameUniforms::BINDING);
         Self {
-            gl: Rc::new(gl),
+            frame: UniformBuffer::create(&gl, FrameUniforms::BINDING),
+            programs,
+            gl,
+            recorder,
             pro_mat: Mat4::default(),
             mod_mat: Mat4::default(),
+            camera: Camera::default(),
+            viewport: Vec4::wrap(0.0, 0.0, 360.0, 480.0),
             stamp: 0.0,
             texture: None,
-            quad: None,
+            scene: Scene::default(),
+            shaders: ShaderLibrary::builtin(),
         }
     }
 }
 
 impl Engine {
     pub fn setup(&mut self) {
-        self.pro_mat.perspective(45.0, 360.0 / 480.0, 0.1, 100.0);
-        self.pro_mat.translate(&Vec3::wrap(0.0, 0.0, -6.0));
-
         let gl = self.gl().clone();
-        self.gl.viewport(0, 0, 360, 480);
-        self.gl.clear_color(0.0, 0.0, 0.0, 1.0);
+        self.gl.viewport(
+            self.viewport.x as i32, self.viewport.y as i32,
+            self.viewport.z as i32, self.viewport.w as i32,
+        );
+        self.set_clear_color(&Color::rgb(0.0, 0.0, 0.0));
         //gl.enable(WebGl::BLEND);
         //gl.blend_func(WebGl::SRC_ALPHA, WebGl::ONE);
         gl.enable(WebGl::DEPTH_TEST);
-        gl.depth_func(WebGl::LEQUAL);
+        self.set_camera(Camera {
+            aspect: self.viewport.z / self.viewport.w,
+            ..Camera::default()
+        });
 
-        self.quad = Some(Quad::create(self).unwrap());
+        match Quad::create(self) {
+            Ok(quad) => {
+                let node = self.scene.add(None);
+                self.scene.node_mut(node).unwrap().renderable = Some(Rc::new(quad));
+            }
+            Err(e) => web_sys::console::error_1(&e.into()),
+        }
         self.texture = utils::load_texture(
             gl.as_ref(), "cubetexture.png",
         );
+        self.recorder.seal();
+    }
+
+    /// Renders one frame and returns every GL call it issued, along with
+    /// the calls from `setup` that created the objects it uses and those
+    /// that set the state it starts from.
+    pub fn capture_frame(&mut self) -> Trace {
+        self.recorder.start();
+        self.update();
+        self.recorder.stop()
+    }
+
+    /// The canvas is an sRGB surface, so `color` is written as-is.
+    pub fn set_clear_color(&self, color: &Color) {
+        self.gl.clear_color(color.r, color.g, color.b, color.a);
+    }
+
+    /// Switches the active camera, reverse-Z cameras clear depth to 0 and
+    /// keep the nearer fragment with `GREATER`. They also switch clip depth
+    /// to `[0, 1]` with `EXT_clip_control`, without it depth is remapped
+    /// from `[-1, 1]` and reverse-Z gains no precision, which is logged.
+    pub fn set_camera(&mut self, camera: Camera) {
+        let depth = match camera.reverse_z {
+            true => ZERO_TO_ONE_EXT,
+            false => NEGATIVE_ONE_TO_ONE_EXT,
+        };
+        let zero_to_one = self.gl.clip_control(LOWER_LEFT_EXT, depth) && camera.reverse_z;
+        if camera.reverse_z && !zero_to_one {
+            utils::warn("EXT_clip_control is unavailable, reverse-Z depth gains no precision");
+        }
+        if camera.reverse_z {
+            self.gl.clear_depth(0.0);
+            self.gl.depth_func(WebGl::GREATER);
+        } else {
+            self.gl.clear_depth(1.0);
+            self.gl.depth_func(WebGl::LEQUAL);
+        }
+        self.pro_mat = camera.projection(zero_to_one);
+        self.camera = camera;
+    }
+
+    /// World-space ray through a canvas pixel, for picking.
+    pub fn screen_ray(&self, x: f32, y: f32) -> Option<Ray> {
+        glm::screen_ray(x, y, &self.viewport, &self.pro_mat, &self.camera.view)
+    }
+
+    /// Canvas pixel position of a world-space point, for anchoring labels.
+    pub fn world_to_screen(&self, point: &Vec3) -> Option<Vec3> {
+        glm::world_to_screen(point, &self.viewport, &self.pro_mat, &self.camera.view)
     }
 
     pub fn input(&mut self, _x: f32, _y: f32, _pressed: bool) {}
 
+    /// Sets the frame time in milliseconds, as passed to
+    /// `requestAnimationFrame` callbacks.
+    pub fn set_time(&mut self, stamp: f64) {
+        self.stamp = stamp;
+    }
+
     pub fn update(&mut self) {
         {
             self.gl.clear(WebGl::COLOR_BUFFER_BIT | WebGl::DEPTH_BUFFER_BIT);
-            self.quad.as_ref().unwrap().draw(self, self.texture.as_ref());
+            let eye = self.camera.view.inverse().unwrap_or_default();
+            self.frame.update(&FrameUniforms {
+                projection: self.pro_mat.clone(),
+                view: self.camera.view.clone(),
+                camera_position: Vec3::wrap(eye[12], eye[13], eye[14]),
+                time: (self.stamp / 1000.0) as f32,
+            });
+            self.scene.update();
+            let mut drawables = Vec::new();
+            self.scene.traverse(|_, node| {
+                if let Some(renderable) = node.renderable.as_ref() {
+                    drawables.push((node.world_matrix().clone(), renderable.clone()));
+                }
+            });
+            for (world, renderable) in drawables {
+                self.mod_mat = world;
+                if let Err(e) = renderable.draw(self, self.texture.as_ref()) {
+                    web_sys::console::error_1(&e.to_string().into());
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use crate::backend::{Call, Headless};
+    use super::*;
+
+    /// Logs the model matrix it is drawn with.
+    struct Probe {
+        name: &'static str,
+        log: Rc<RefCell<Vec<(&'static str, Mat4)>>>,
+    }
+
+    impl Renderable for Probe {
+        fn draw(&self, context: &dyn Context, _: Option<&Texture>) -> Result<(), UniformError> {
+            self.log.borrow_mut().push((self.name, context.mod_matrix().clone()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn update_draws_each_node_with_its_world_matrix() {
+        let mut engine = Engine::create(Rc::new(Headless::default()));
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let probe = |name| Some(Rc::new(Probe { name, log: log.clone() }) as Rc<dyn Renderable>);
+        let parent = engine.scene.add(None);
+        let child = engine.scene.add(Some(parent));
+        let sibling = engine.scene.add(None);
+        for (id, name) in [(parent, "parent"), (child, "child"), (sibling, "sibling")] {
+            engine.scene.node_mut(id).unwrap().renderable = probe(name);
+        }
+        engine.scene.node_mut(parent).unwrap().set_translation(Vec3::wrap(1.0, 0.0, 0.0));
+        engine.scene.node_mut(child).unwrap().set_translation(Vec3::wrap(0.0, 2.0, 0.0));
+        engine.scene.node_mut(sibling).unwrap().set_scale(Vec3::wrap(3.0, 3.0, 3.0));
+        engine.update();
+        assert_eq!(*log.borrow(), [
+            ("parent", Mat4::from_translation(&Vec3::wrap(1.0, 0.0, 0.0))),
+            ("child", Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 0.0))),
+            ("sibling", Mat4::from_scale(&Vec3::wrap(3.0, 3.0, 3.0))),
+        ]);
+    }
+
+    #[test]
+    fn reverse_z_uses_clip_control_when_available() {
+        let reverse = || Camera { reverse_z: true, far: None, ..Camera::default() };
+        for available in [false, true] {
+            let gl = Rc::new(Headless::default());
+            gl.set_clip_control(available);
+            let mut engine = Engine::create(gl.clone());
+            gl.take_calls();
+            engine.set_camera(reverse());
+            let expected = reverse().projection(available);
+            assert_eq!(engine.pro_mat, expected);
+            assert_eq!(expected == reverse().projection(false), !available);
+            let clip = gl.take_calls().into_iter()
+                .filter(|call| matches!(call, Call::ClipControl(..)))
+                .collect::<Vec<_>>();
+            match available {
+                true => assert_eq!(clip, [Call::ClipControl(LOWER_LEFT_EXT, ZERO_TO_ONE_EXT)]),
+                false => assert!(clip.is_empty()),
+            }
+        }
+        // Switching back restores the GL clip range.
+        let gl = Rc::new(Headless::default());
+        gl.set_clip_control(true);
+        let mut engine = Engine::create(gl.clone());
+        engine.set_camera(reverse());
+        gl.take_calls();
+        engine.set_camera(Camera::default());
+        assert!(gl.calls().contains(&Call::ClipControl(LOWER_LEFT_EXT, NEGATIVE_ONE_TO_ONE_EXT)));
+        assert_eq!(engine.pro_mat, Camera::default().projection(false));
+    }
+}
@@ -0,0 +1,92 @@
+use super::{Mat4, Vec3};
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn wrap(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns `None` for an empty slice.
+    pub fn from_points(points: &[Vec3]) -> Option<Self> {
+        let first = points.first()?;
+        Some(points.iter().fold(
+            Self::wrap(*first, *first),
+            |aabb, p| Self::wrap(aabb.min.min(p), aabb.max.max(p)),
+        ))
+    }
+}
+
+impl Aabb {
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Half the size along each axis.
+    pub fn extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn union(&self, rhs: &Self) -> Self {
+        Self::wrap(self.min.min(&rhs.min), self.max.max(&rhs.max))
+    }
+
+    pub fn contains_point(&self, point: &Vec3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    pub fn intersects(&self, rhs: &Self) -> bool {
+        self.min.x <= rhs.max.x && self.max.x >= rhs.min.x
+            && self.min.y <= rhs.max.y && self.max.y >= rhs.min.y
+            && self.min.z <= rhs.max.z && self.max.z >= rhs.min.z
+    }
+
+    /// Smallest box enclosing this box after an affine transform.
+    pub fn transform(&self, mat: &Mat4) -> Self {
+        let center = mat.transform_point(&self.center());
+        let e = self.extents();
+        let extents = Vec3::wrap(
+            mat[0].abs() * e.x + mat[4].abs() * e.y + mat[8].abs() * e.z,
+            mat[1].abs() * e.x + mat[5].abs() * e.y + mat[9].abs() * e.z,
+            mat[2].abs() * e.x + mat[6].abs() * e.y + mat[10].abs() * e.z,
+        );
+        Self::wrap(center - extents, center + extents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let a = Aabb::from_points(&[Vec3::wrap(1.0, -1.0, 0.0), Vec3::wrap(-2.0, 3.0, 1.0)]).unwrap();
+        assert_eq!(a, Aabb::wrap(Vec3::wrap(-2.0, -1.0, 0.0), Vec3::wrap(1.0, 3.0, 1.0)));
+        assert_eq!(Aabb::from_points(&[]), None);
+        assert!(a.contains_point(&Vec3::wrap(1.0, 3.0, 1.0)));
+        assert!(!a.contains_point(&Vec3::wrap(1.5, 0.0, 0.5)));
+        let b = Aabb::wrap(Vec3::wrap(1.0, 0.0, 0.0), Vec3::wrap(2.0, 1.0, 1.0));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&Aabb::wrap(Vec3::wrap(1.1, 0.0, 0.0), Vec3::wrap(2.0, 1.0, 1.0))));
+        assert_eq!(a.union(&b).max, Vec3::wrap(2.0, 3.0, 1.0));
+    }
+
+    #[test]
+    fn transform_encloses_rotated_box() {
+        let a = Aabb::wrap(Vec3::wrap(-1.0, -1.0, -1.0), Vec3::wrap(1.0, 1.0, 1.0));
+        let m = &Mat4::from_translation(&Vec3::wrap(5.0, 0.0, 0.0))
+            * &Mat4::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4);
+        let t = a.transform(&m);
+        let r = 2f32.sqrt();
+        assert!((t.max.x - (5.0 + r)).abs() < 1e-5 && (t.max.y - r).abs() < 1e-5 && (t.max.z - 1.0).abs() < 1e-5);
+        assert!((t.min.x - (5.0 - r)).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,263 @@
+use super::Vec4;
+
+/// RGBA color with channels in `[0, 1]`. The type does not track its color
+/// space, use `to_linear`/`to_srgb` at the boundaries, e.g. sRGB values
+/// from a color picker must be made linear before lighting math.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }
+    }
+}
+
+impl From<Color> for Vec4 {
+    fn from(c: Color) -> Self {
+        Vec4::wrap(c.r, c.g, c.b, c.a)
+    }
+}
+
+impl From<Vec4> for Color {
+    fn from(v: Vec4) -> Self {
+        Color::wrap(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(c: Color) -> Self {
+        [c.r, c.g, c.b, c.a]
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl Color {
+    pub fn wrap(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, the `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let bad = || format!("Invalid hex color: {}", hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(bad());
+        }
+        let nibble = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).unwrap();
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+        let channels: Vec<u8> = match digits.len() {
+            3 | 4 => (0..digits.len()).map(|i| nibble(i) * 17).collect(),
+            6 | 8 => (0..digits.len()).step_by(2).map(byte).collect(),
+            _ => return Err(bad()),
+        };
+        let f = |i: usize| channels.get(i).map_or(1.0, |c| *c as f32 / 255.0);
+        Ok(Self::wrap(f(0), f(1), f(2), f(3)))
+    }
+
+    /// `h` in degrees, `s` and `v` in `[0, 1]`.
+    pub fn from_hsv(h: f32, s: f32, v: f32, a: f32) -> Self {
+        let c = v * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = v - c;
+        Self::wrap(r + m, g + m, b + m, a)
+    }
+
+    /// `h` in degrees, `s` and `l` in `[0, 1]`.
+    pub fn from_hsl(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = l - c * 0.5;
+        Self::wrap(r + m, g + m, b + m, a)
+    }
+}
+
+/// RGB with chroma `c` and the given hue, before adding the lightness offset.
+fn hue_to_rgb(h: f32, c: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+impl Color {
+    pub fn to_linear(&self) -> Self {
+        Self::wrap(srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
+
+    pub fn to_srgb(&self) -> Self {
+        Self::wrap(linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a)
+    }
+
+    /// Hue in degrees, saturation and value.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (h, max, min) = self.hue();
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (h, s, max)
+    }
+
+    /// Hue in degrees, saturation and lightness.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (h, max, min) = self.hue();
+        let l = (max + min) * 0.5;
+        let d = max - min;
+        let s = if d > 0.0 { d / (1.0 - (2.0 * l - 1.0).abs()) } else { 0.0 };
+        (h, s, l)
+    }
+
+    fn hue(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let d = max - min;
+        let h = if d == 0.0 {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / d).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / d + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / d + 4.0)
+        };
+        (h, max, min)
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when not fully opaque.
+    pub fn to_hex(&self) -> String {
+        let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if byte(self.a) == 255 {
+            format!("#{:02x}{:02x}{:02x}", byte(self.r), byte(self.g), byte(self.b))
+        } else {
+            format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                byte(self.r), byte(self.g), byte(self.b), byte(self.a),
+            )
+        }
+    }
+
+    pub fn premultiply(&self) -> Self {
+        Self::wrap(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Inverse of `premultiply`, fully transparent colors become black.
+    pub fn unpremultiply(&self) -> Self {
+        if self.a == 0.0 {
+            Self::wrap(0.0, 0.0, 0.0, 0.0)
+        } else {
+            Self::wrap(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+        }
+    }
+
+    /// Component-wise, interpolate in linear space for physically correct blends.
+    pub fn lerp(&self, rhs: &Self, t: f32) -> Self {
+        Self::wrap(
+            self.r + (rhs.r - self.r) * t,
+            self.g + (rhs.g - self.g) * t,
+            self.b + (rhs.b - self.b) * t,
+            self.a + (rhs.a - self.a) * t,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &Color, b: &Color) -> bool {
+        let (a, b): ([f32; 4], [f32; 4]) = ((*a).into(), (*b).into());
+        a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    #[test]
+    fn parses_hex() {
+        assert_eq!(Color::from_hex("#f80").unwrap(), Color::from_hex("#ff8800").unwrap());
+        assert_eq!(Color::from_hex("#ff8000").unwrap(), Color::rgb(1.0, 128.0 / 255.0, 0.0));
+        let half = 128.0 / 255.0;
+        assert_eq!(Color::from_hex("ff800080").unwrap(), Color::wrap(1.0, half, 0.0, half));
+        assert_eq!(Color::from_hex("#f80c").unwrap().to_hex(), "#ff8800cc");
+        assert_eq!(Color::from_hex("#ff8000").unwrap().to_hex(), "#ff8000");
+        for bad in ["", "#", "#12345", "#1234567", "#zzzzzz", "#ff80é", "##fff"] {
+            assert_eq!(Color::from_hex(bad), Err(format!("Invalid hex color: {}", bad)));
+        }
+    }
+
+    #[test]
+    fn srgb_round_trips_around_the_knee() {
+        for c in [0.0, 0.01, 0.04, 0.04045, 0.0405, 0.05, 0.5, 1.0] {
+            let srgb = Color::wrap(c, c, c, 0.25);
+            assert!(close(&srgb.to_linear().to_srgb(), &srgb), "{}", c);
+        }
+        // Both branches meet at the knee.
+        assert_eq!(srgb_to_linear(0.04045), 0.04045 / 12.92);
+        assert!((srgb_to_linear(0.040451) - srgb_to_linear(0.04045)).abs() < 1e-6);
+        assert!((linear_to_srgb(0.0031309) - linear_to_srgb(0.0031308)).abs() < 1e-5);
+        assert!((Color::rgb(0.5, 0.5, 0.5).to_linear().r - 0.21404).abs() < 1e-4);
+        assert_eq!(Color::wrap(0.2, 0.2, 0.2, 0.25).to_linear().a, 0.25);
+    }
+
+    #[test]
+    fn hsv_and_hsl_round_trip_in_every_sector() {
+        let colors = [
+            Color::rgb(0.9, 0.4, 0.1),
+            Color::rgb(0.5, 0.8, 0.2),
+            Color::rgb(0.1, 0.9, 0.6),
+            Color::rgb(0.2, 0.4, 0.9),
+            Color::rgb(0.6, 0.1, 0.8),
+            Color::rgb(0.9, 0.2, 0.5),
+        ];
+        for (sector, color) in colors.iter().enumerate() {
+            let (h, s, v) = color.to_hsv();
+            assert_eq!((h / 60.0) as usize, sector);
+            assert!(close(&Color::from_hsv(h, s, v, 1.0), color), "{:?}", color);
+            let (h, s, l) = color.to_hsl();
+            assert_eq!((h / 60.0) as usize, sector);
+            assert!(close(&Color::from_hsl(h, s, l, 1.0), color), "{:?}", color);
+        }
+        let (h, s, v) = Color::from_hex("#ff8000").unwrap().to_hsv();
+        assert!((h - 30.1176).abs() < 1e-3 && s == 1.0 && v == 1.0);
+        assert_eq!(Color::rgb(0.5, 0.5, 0.5).to_hsl(), (0.0, 0.0, 0.5));
+        assert!(close(&Color::from_hsv(-120.0, 1.0, 1.0, 1.0), &Color::rgb(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn premultiply_and_lerp() {
+        let c = Color::wrap(0.8, 0.4, 0.2, 0.5);
+        assert!(close(&c.premultiply(), &Color::wrap(0.4, 0.2, 0.1, 0.5)));
+        assert!(close(&c.premultiply().unpremultiply(), &c));
+        let clear = Color::wrap(0.0, 0.0, 0.0, 0.0);
+        assert_eq!(Color::wrap(0.3, 0.2, 0.1, 0.0).unpremultiply(), clear);
+        let (a, b) = (Color::rgb(0.0, 0.5, 1.0), Color::wrap(1.0, 0.5, 0.0, 0.0));
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert!(close(&a.lerp(&b, 0.25), &Color::wrap(0.25, 0.5, 0.75, 0.75)));
+    }
+}
@@ -0,0 +1,111 @@
+// Conversions with the math types of other crates, enabled by the
+// `mint` and `glam` features. Matrices go through the column-major array.
+
+#[cfg(feature = "mint")]
+mod mint_impl {
+    use crate::glm::{Mat4, Vec3};
+
+    impl From<mint::Vector3<f32>> for Vec3 {
+        fn from(v: mint::Vector3<f32>) -> Self {
+            Self::wrap(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vec3> for mint::Vector3<f32> {
+        fn from(v: Vec3) -> Self {
+            Self { x: v.x, y: v.y, z: v.z }
+        }
+    }
+
+    impl From<mint::ColumnMatrix4<f32>> for Mat4 {
+        fn from(m: mint::ColumnMatrix4<f32>) -> Self {
+            let data: [f32; 16] = m.into();
+            Self::from_cols_array(&data)
+        }
+    }
+
+    impl From<Mat4> for mint::ColumnMatrix4<f32> {
+        fn from(m: Mat4) -> Self {
+            m.to_cols_array().into()
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impl {
+    use crate::glm::{Mat4, Vec3};
+
+    impl From<glam::Vec3> for Vec3 {
+        fn from(v: glam::Vec3) -> Self {
+            Self::wrap(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vec3> for glam::Vec3 {
+        fn from(v: Vec3) -> Self {
+            Self::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<glam::Mat4> for Mat4 {
+        fn from(m: glam::Mat4) -> Self {
+            Self::from_cols_array(&m.to_cols_array())
+        }
+    }
+
+    impl From<Mat4> for glam::Mat4 {
+        fn from(m: Mat4) -> Self {
+            Self::from_cols_array(&m.to_cols_array())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glm::{Mat4, Quat, Vec3};
+
+    fn sample() -> Mat4 {
+        let rotation = Quat::from_euler(0.3, -1.2, 2.5);
+        Mat4::from_trs(&Vec3::wrap(1.0, 2.0, 3.0), &rotation, &Vec3::wrap(2.0, 1.0, 0.5))
+    }
+
+    #[test]
+    fn cols_array_round_trips() {
+        let data: [f32; 16] = core::array::from_fn(|i| i as f32);
+        assert_eq!(Mat4::from_cols_array(&data).to_cols_array(), data);
+        let m = sample();
+        assert_eq!(Mat4::from_cols_array(&m.to_cols_array()), m);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn glam_keeps_the_column_order() {
+        let m = Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 3.0));
+        let g: glam::Mat4 = m.clone().into();
+        assert_eq!(g, glam::Mat4::from_translation(glam::Vec3::new(1.0, 2.0, 3.0)));
+        assert_eq!(g.w_axis, glam::Vec4::new(1.0, 2.0, 3.0, 1.0));
+        assert_eq!(Mat4::from(g), m);
+        //
+        let m = sample();
+        let g = glam::Mat4::from(m.clone());
+        assert_eq!(g.to_cols_array(), m.to_cols_array());
+        let p = Vec3::wrap(-0.5, 4.0, 1.5);
+        let (a, b) = (m.transform_point(&p), g.transform_point3(p.into()));
+        assert!((a.x - b.x).abs() < 1e-5 && (a.y - b.y).abs() < 1e-5 && (a.z - b.z).abs() < 1e-5);
+        assert_eq!(Mat4::from(glam::Mat4::from_cols_array(&m.to_cols_array())), m);
+        assert_eq!(Vec3::from(glam::Vec3::from(p)), p);
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn mint_keeps_the_column_order() {
+        let m = Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 3.0));
+        let c: mint::ColumnMatrix4<f32> = m.clone().into();
+        assert_eq!(c.w, mint::Vector4 { x: 1.0, y: 2.0, z: 3.0, w: 1.0 });
+        assert_eq!(Mat4::from(c), m);
+        let m = sample();
+        assert_eq!(Mat4::from(mint::ColumnMatrix4::from(m.clone())), m);
+        let p = Vec3::wrap(-0.5, 4.0, 1.5);
+        assert_eq!(Vec3::from(mint::Vector3::from(p)), p);
+    }
+}
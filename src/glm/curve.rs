@@ -0,0 +1,293 @@
+use super::Vec3;
+
+/// Parametric curve over `t` in `[0, 1]`.
+pub trait Curve {
+    fn point(&self, t: f32) -> Vec3;
+
+    /// First derivative with respect to `t`, not normalized.
+    fn tangent(&self, t: f32) -> Vec3;
+
+    /// Polyline of `segments + 1` points evenly spaced in `t`.
+    fn sample(&self, segments: usize) -> Vec<Vec3> {
+        let segments = segments.max(1);
+        (0..=segments)
+            .map(|i| self.point(i as f32 / segments as f32))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier {
+    pub p0: Vec3,
+    pub p1: Vec3,
+    pub p2: Vec3,
+    pub p3: Vec3,
+}
+
+impl CubicBezier {
+    pub fn wrap(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn point(&self, t: f32) -> Vec3 {
+        let u = 1.0 - t;
+        self.p0 * (u * u * u)
+            + self.p1 * (3.0 * u * u * t)
+            + self.p2 * (3.0 * u * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    fn tangent(&self, t: f32) -> Vec3 {
+        let u = 1.0 - t;
+        (self.p1 - self.p0) * (3.0 * u * u)
+            + (self.p2 - self.p1) * (6.0 * u * t)
+            + (self.p3 - self.p2) * (3.0 * t * t)
+    }
+}
+
+/// Cubic Hermite segment from `p0` to `p1` with tangents `m0` and `m1`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hermite {
+    pub p0: Vec3,
+    pub m0: Vec3,
+    pub p1: Vec3,
+    pub m1: Vec3,
+}
+
+impl Hermite {
+    pub fn wrap(p0: Vec3, m0: Vec3, p1: Vec3, m1: Vec3) -> Self {
+        Self { p0, m0, p1, m1 }
+    }
+}
+
+impl Curve for Hermite {
+    fn point(&self, t: f32) -> Vec3 {
+        let (t2, t3) = (t * t, t * t * t);
+        self.p0 * (2.0 * t3 - 3.0 * t2 + 1.0)
+            + self.m0 * (t3 - 2.0 * t2 + t)
+            + self.p1 * (-2.0 * t3 + 3.0 * t2)
+            + self.m1 * (t3 - t2)
+    }
+
+    fn tangent(&self, t: f32) -> Vec3 {
+        let t2 = t * t;
+        self.p0 * (6.0 * t2 - 6.0 * t)
+            + self.m0 * (3.0 * t2 - 4.0 * t + 1.0)
+            + self.p1 * (-6.0 * t2 + 6.0 * t)
+            + self.m1 * (3.0 * t2 - 2.0 * t)
+    }
+}
+
+/// Uniform Catmull-Rom spline passing through every control point, `t`
+/// spans the whole spline with one equal share per segment.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatmullRom {
+    pub points: Vec<Vec3>,
+    pub closed: bool,
+}
+
+impl CatmullRom {
+    pub fn wrap(points: Vec<Vec3>, closed: bool) -> Self {
+        Self { points, closed }
+    }
+
+    fn segments(&self) -> usize {
+        match self.points.len() {
+            0 | 1 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+
+    /// Segment `i` as a Hermite curve, open ends reuse the end point.
+    pub fn segment(&self, i: usize) -> Hermite {
+        let n = self.points.len() as isize;
+        let at = |k: isize| -> Vec3 {
+            if self.closed {
+                self.points[k.rem_euclid(n) as usize]
+            } else {
+                self.points[k.clamp(0, n - 1) as usize]
+            }
+        };
+        let i = i as isize;
+        let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        Hermite::wrap(p1, (p2 - p0) * 0.5, p2, (p3 - p1) * 0.5)
+    }
+
+    /// Maps the global `t` to a segment index and its local parameter.
+    fn locate(&self, t: f32) -> (usize, f32) {
+        let count = self.segments();
+        let scaled = t.clamp(0.0, 1.0) * count as f32;
+        let i = (scaled as usize).min(count - 1);
+        (i, scaled - i as f32)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn point(&self, t: f32) -> Vec3 {
+        match self.segments() {
+            0 => self.points.first().copied().unwrap_or_default(),
+            _ => {
+                let (i, u) = self.locate(t);
+                self.segment(i).point(u)
+            }
+        }
+    }
+
+    fn tangent(&self, t: f32) -> Vec3 {
+        match self.segments() {
+            0 => Vec3::default(),
+            n => {
+                let (i, u) = self.locate(t);
+                self.segment(i).tangent(u) * n as f32
+            }
+        }
+    }
+}
+
+/// Cumulative length table of a curve, for moving along it at constant
+/// speed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ArcLength {
+    lengths: Vec<f32>,
+}
+
+impl ArcLength {
+    /// Approximates the curve by `segments` chords.
+    pub fn build(curve: &dyn Curve, segments: usize) -> Self {
+        let points = curve.sample(segments);
+        let mut lengths = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        lengths.push(0.0);
+        for pair in points.windows(2) {
+            total += pair[0].distance(&pair[1]);
+            lengths.push(total);
+        }
+        Self { lengths }
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Curve parameter `t` at the given distance from the start.
+    pub fn param_at(&self, distance: f32) -> f32 {
+        let segments = self.lengths.len() - 1;
+        let distance = distance.clamp(0.0, self.length());
+        let i = self.lengths.partition_point(|l| *l < distance);
+        if i == 0 {
+            return 0.0;
+        }
+        let (a, b) = (self.lengths[i - 1], self.lengths[i]);
+        let f = if b > a { (distance - a) / (b - a) } else { 0.0 };
+        ((i - 1) as f32 + f) / segments as f32
+    }
+
+    /// Polyline of `segments + 1` points evenly spaced along the curve.
+    pub fn sample(&self, curve: &dyn Curve, segments: usize) -> Vec<Vec3> {
+        let segments = segments.max(1);
+        let length = self.length();
+        (0..=segments)
+            .map(|i| curve.point(self.param_at(length * i as f32 / segments as f32)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close3(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-3
+    }
+
+    /// Central difference of `point`, to check the analytic tangents.
+    fn numeric_tangent(curve: &dyn Curve, t: f32) -> Vec3 {
+        let h = 1e-3;
+        (curve.point(t + h) - curve.point(t - h)) / (2.0 * h)
+    }
+
+    #[test]
+    fn bezier() {
+        let p = [
+            Vec3::wrap(0.0, 0.0, 0.0),
+            Vec3::wrap(1.0, 2.0, 0.0),
+            Vec3::wrap(3.0, 2.0, 1.0),
+            Vec3::wrap(4.0, 0.0, 1.0),
+        ];
+        let curve = CubicBezier::wrap(p[0], p[1], p[2], p[3]);
+        assert!(close3(curve.point(0.0), p[0]) && close3(curve.point(1.0), p[3]));
+        assert!(close3(curve.point(0.5), Vec3::wrap(2.0, 1.5, 0.5)));
+        assert!(close3(curve.tangent(0.0), (p[1] - p[0]) * 3.0));
+        assert!(close3(curve.tangent(1.0), (p[3] - p[2]) * 3.0));
+        for t in [0.2, 0.5, 0.9] {
+            assert!((curve.tangent(t) - numeric_tangent(&curve, t)).length() < 1e-2);
+        }
+        assert_eq!(curve.sample(0).len(), 2);
+        assert_eq!(curve.sample(4).len(), 5);
+    }
+
+    #[test]
+    fn hermite() {
+        let curve = Hermite::wrap(
+            Vec3::wrap(0.0, 0.0, 0.0), Vec3::wrap(1.0, 1.0, 0.0),
+            Vec3::wrap(2.0, 0.0, 0.0), Vec3::wrap(1.0, -1.0, 0.0),
+        );
+        assert!(close3(curve.point(0.0), curve.p0) && close3(curve.point(1.0), curve.p1));
+        assert!(close3(curve.tangent(0.0), curve.m0) && close3(curve.tangent(1.0), curve.m1));
+        for t in [0.2, 0.5, 0.9] {
+            assert!((curve.tangent(t) - numeric_tangent(&curve, t)).length() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn catmull_rom_interpolates_its_points() {
+        let points = vec![
+            Vec3::wrap(0.0, 0.0, 0.0),
+            Vec3::wrap(1.0, 1.0, 0.0),
+            Vec3::wrap(2.0, 0.0, 0.0),
+            Vec3::wrap(3.0, 1.0, 0.0),
+        ];
+        let open = CatmullRom::wrap(points.clone(), false);
+        for (i, p) in points.iter().enumerate() {
+            assert!(close3(open.point(i as f32 / 3.0), *p));
+        }
+        assert!(close3(open.point(-1.0), points[0]) && close3(open.point(2.0), points[3]));
+        // Segments meet with matching tangents.
+        assert!(close3(open.segment(0).tangent(1.0), open.segment(1).tangent(0.0)));
+        assert!((open.tangent(0.5) - numeric_tangent(&open, 0.5)).length() < 1e-2);
+        //
+        let closed = CatmullRom::wrap(points.clone(), true);
+        assert!(close3(closed.point(0.75), points[3]));
+        assert!(close3(closed.point(1.0), points[0]));
+        assert!(close3(closed.segment(3).p1, points[0]));
+        //
+        let single = CatmullRom::wrap(vec![points[1]], false);
+        assert!(close3(single.point(0.3), points[1]));
+        assert_eq!(single.tangent(0.3), Vec3::default());
+        assert_eq!(CatmullRom::wrap(Vec::new(), true).point(0.5), Vec3::default());
+    }
+
+    #[test]
+    fn arc_length_moves_at_constant_speed() {
+        // A straight line whose control points bunch up at the start.
+        let line = CubicBezier::wrap(
+            Vec3::wrap(0.0, 0.0, 0.0), Vec3::wrap(0.1, 0.0, 0.0),
+            Vec3::wrap(0.2, 0.0, 0.0), Vec3::wrap(4.0, 0.0, 0.0),
+        );
+        let table = ArcLength::build(&line, 256);
+        assert!((table.length() - 4.0).abs() < 1e-3);
+        assert_eq!(table.param_at(-1.0), 0.0);
+        assert_eq!(table.param_at(10.0), 1.0);
+        let points = table.sample(&line, 8);
+        assert_eq!(points.len(), 9);
+        for (i, p) in points.iter().enumerate() {
+            assert!((p.x - i as f32 * 0.5).abs() < 1e-2, "{}: {:?}", i, p);
+        }
+    }
+}
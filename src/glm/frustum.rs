@@ -0,0 +1,89 @@
+use super::{Aabb, Mat4, Plane, Sphere, Vec3};
+
+/// Six inward-facing planes: left, right, bottom, top, near, far.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a view-projection matrix, e.g. `pro * mod`.
+    /// Planes are in the space the matrix maps from.
+    pub fn from_matrix(mat: &Mat4) -> Self {
+        let row = |i: usize| (mat[i], mat[4 + i], mat[8 + i], mat[12 + i]);
+        let w = row(3);
+        let plane = |r: (f32, f32, f32, f32), sign: f32| Plane::wrap(
+            Vec3::wrap(w.0 + sign * r.0, w.1 + sign * r.1, w.2 + sign * r.2),
+            w.3 + sign * r.3,
+        ).normalize();
+        Self {
+            planes: [
+                plane(row(0), 1.0),
+                plane(row(0), -1.0),
+                plane(row(1), 1.0),
+                plane(row(1), -1.0),
+                plane(row(2), 1.0),
+                plane(row(2), -1.0),
+            ]
+        }
+    }
+}
+
+impl Frustum {
+    pub fn contains_point(&self, point: &Vec3) -> bool {
+        self.planes.iter().all(|p| p.distance(point) >= 0.0)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|p| p.distance(&sphere.center) >= -sphere.radius)
+    }
+
+    /// Conservative test, boxes near the frustum corners may be reported
+    /// as intersecting although they lie outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|p| {
+            let corner = Vec3::wrap(
+                if p.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if p.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if p.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            p.distance(&corner) >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frustum() -> Frustum {
+        // Looking down -Z from the origin, near 1, far 10.
+        Frustum::from_matrix(&Mat4::perspective_rh(90f32.to_radians(), 1.0, 1.0, 10.0))
+    }
+
+    #[test]
+    fn points() {
+        let f = frustum();
+        assert!(f.contains_point(&Vec3::wrap(0.0, 0.0, -5.0)));
+        assert!(f.contains_point(&Vec3::wrap(4.0, -4.0, -5.0)));
+        assert!(!f.contains_point(&Vec3::wrap(6.0, 0.0, -5.0)));
+        assert!(!f.contains_point(&Vec3::wrap(0.0, 0.0, -0.5)));
+        assert!(!f.contains_point(&Vec3::wrap(0.0, 0.0, -11.0)));
+        assert!(!f.contains_point(&Vec3::wrap(0.0, 0.0, 5.0)));
+    }
+
+    #[test]
+    fn spheres_and_boxes() {
+        let f = frustum();
+        assert!(f.intersects_sphere(&Sphere::wrap(Vec3::wrap(0.0, 0.0, -5.0), 0.5)));
+        assert!(f.intersects_sphere(&Sphere::wrap(Vec3::wrap(0.0, 0.0, -11.0), 2.0)));
+        assert!(!f.intersects_sphere(&Sphere::wrap(Vec3::wrap(0.0, 0.0, 5.0), 2.0)));
+        let cube = |z: f32, h: f32| Aabb::wrap(Vec3::wrap(-h, -h, z - h), Vec3::wrap(h, h, z + h));
+        assert!(f.intersects_aabb(&cube(-5.0, 1.0)));
+        assert!(f.intersects_aabb(&cube(-5.0, 100.0)));
+        assert!(f.intersects_aabb(&cube(-0.5, 0.6)));
+        assert!(!f.intersects_aabb(&cube(5.0, 1.0)));
+        assert!(!f.intersects_aabb(&Aabb::wrap(Vec3::wrap(20.0, -1.0, -6.0), Vec3::wrap(22.0, 1.0, -4.0))));
+    }
+}
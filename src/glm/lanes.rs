@@ -0,0 +1,74 @@
+// Lane tables of the simd128 `inverse` in `simd.rs`. They live apart from
+// the intrinsics so the native tests below can replay them with plain
+// floats and compare against `scalar::inverse`.
+
+// The 2x2 minors `b[0..12]`, four per vector: `a * b - c * d`.
+pub const MINOR_A: [[usize; 4]; 3] = [[0, 0, 0, 1], [1, 2, 8, 8], [8, 9, 9, 10]];
+pub const MINOR_B: [[usize; 4]; 3] = [[5, 6, 7, 6], [7, 7, 13, 14], [15, 14, 15, 15]];
+pub const MINOR_C: [[usize; 4]; 3] = [[1, 2, 3, 2], [3, 3, 9, 10], [11, 10, 11, 11]];
+pub const MINOR_D: [[usize; 4]; 3] = [[4, 4, 4, 5], [5, 6, 12, 12], [12, 13, 13, 14]];
+
+// Each output lane is `p * b1 - q * b2 + r * b3`, rows of the scalar code
+// written as `x - y - z` are folded into `x - y + (-z)` by negating `r`.
+pub const INV_P: [[usize; 4]; 4] = [[5, 2, 13, 10], [6, 0, 14, 8], [4, 1, 12, 9], [5, 0, 13, 8]];
+pub const INV_Q: [[usize; 4]; 4] = [[6, 1, 14, 9], [4, 2, 12, 10], [5, 0, 13, 8], [4, 1, 12, 9]];
+pub const INV_R: [[usize; 4]; 4] = [[7, 3, 15, 11], [7, 3, 15, 11], [7, 3, 15, 11], [6, 2, 14, 10]];
+pub const INV_B1: [[usize; 4]; 4] = [[11, 10, 5, 4], [8, 11, 2, 5], [10, 8, 4, 2], [7, 9, 1, 3]];
+pub const INV_B2: [[usize; 4]; 4] = [[10, 11, 4, 5], [11, 8, 5, 2], [8, 10, 2, 4], [9, 7, 3, 1]];
+pub const INV_B3: [[usize; 4]; 4] = [[9, 9, 3, 3], [7, 7, 1, 1], [6, 6, 0, 0], [6, 6, 0, 0]];
+pub const INV_NEG: [[bool; 4]; 4] = [
+    [false, true, false, true],
+    [true, false, true, false],
+    [false, true, false, true],
+    [true, false, true, false],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::scalar;
+
+    /// `simd::inverse` with every vector operation done lane by lane, in
+    /// the same order and on the same tables.
+    fn emulated_inverse(a: &[f32; 16]) -> Option<[f32; 16]> {
+        let mut b = [0f32; 12];
+        for i in 0..3 {
+            for lane in 0..4 {
+                b[i * 4 + lane] = a[MINOR_A[i][lane]] * a[MINOR_B[i][lane]]
+                    - a[MINOR_C[i][lane]] * a[MINOR_D[i][lane]];
+            }
+        }
+        let det = b[0] * b[11] - b[1] * b[10] + b[2] * b[9]
+            + b[3] * b[8] - b[4] * b[7] + b[5] * b[6];
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv = 1.0 / det;
+        let mut out = [0f32; 16];
+        for i in 0..4 {
+            for lane in 0..4 {
+                let r = a[INV_R[i][lane]];
+                let r = if INV_NEG[i][lane] { -r } else { r };
+                let acc = a[INV_P[i][lane]] * b[INV_B1[i][lane]] - a[INV_Q[i][lane]] * b[INV_B2[i][lane]];
+                out[i * 4 + lane] = (acc + r * b[INV_B3[i][lane]]) * inv;
+            }
+        }
+        Some(out)
+    }
+
+    #[test]
+    fn tables_reproduce_the_scalar_inverse_bit_for_bit() {
+        let mut state = 0x2545_f491u32;
+        for _ in 0..1024 {
+            let m = [0f32; 16].map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 8) as f32 / (1 << 24) as f32 * 20.0 - 10.0
+            });
+            let bits = |m: Option<[f32; 16]>| m.map(|m| m.map(f32::to_bits));
+            assert_eq!(bits(emulated_inverse(&m)), bits(scalar::inverse(&m)), "{:?}", m);
+        }
+        assert_eq!(emulated_inverse(&[0.0; 16]), None);
+    }
+}
@@ -0,0 +1,180 @@
+macro_rules! impl_vec_ops {
+    ($name:ident { $($field:ident),+ }) => {
+        impl std::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self { $($field: self.$field * rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::Mul<f32> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl std::ops::Mul<$name> for f32 {
+            type Output = $name;
+
+            fn mul(self, rhs: $name) -> $name {
+                rhs * self
+            }
+        }
+
+        impl std::ops::Div for $name {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                Self { $($field: self.$field / rhs.$field),+ }
+            }
+        }
+
+        impl std::ops::Div<f32> for $name {
+            type Output = Self;
+
+            fn div(self, rhs: f32) -> Self {
+                Self { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl std::ops::Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl std::ops::AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl std::ops::SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        impl std::ops::MulAssign<f32> for $name {
+            fn mul_assign(&mut self, rhs: f32) {
+                $(self.$field *= rhs;)+
+            }
+        }
+
+        impl std::ops::DivAssign<f32> for $name {
+            fn div_assign(&mut self, rhs: f32) {
+                $(self.$field /= rhs;)+
+            }
+        }
+
+        impl $name {
+            pub fn dot(&self, rhs: &Self) -> f32 {
+                0.0 $(+ self.$field * rhs.$field)+
+            }
+
+            pub fn length_squared(&self) -> f32 {
+                self.dot(self)
+            }
+
+            pub fn length(&self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            pub fn distance(&self, rhs: &Self) -> f32 {
+                (*rhs - *self).length()
+            }
+
+            /// Returns the unit vector, a zero vector stays zero.
+            pub fn normalize(&self) -> Self {
+                let len = self.length();
+                if len != 0.0 {
+                    *self / len
+                } else {
+                    *self
+                }
+            }
+
+            pub fn lerp(&self, rhs: &Self, t: f32) -> Self {
+                Self { $($field: self.$field + (rhs.$field - self.$field) * t),+ }
+            }
+
+            pub fn min(&self, rhs: &Self) -> Self {
+                Self { $($field: self.$field.min(rhs.$field)),+ }
+            }
+
+            pub fn max(&self, rhs: &Self) -> Self {
+                Self { $($field: self.$field.max(rhs.$field)),+ }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::glm::{Vec2, Vec3, Vec4};
+
+    #[test]
+    fn component_wise_operators() {
+        let a = Vec3::wrap(1.0, 2.0, 3.0);
+        let b = Vec3::wrap(4.0, -5.0, 6.0);
+        assert_eq!(a + b, Vec3::wrap(5.0, -3.0, 9.0));
+        assert_eq!(a - b, Vec3::wrap(-3.0, 7.0, -3.0));
+        assert_eq!(a * b, Vec3::wrap(4.0, -10.0, 18.0));
+        assert_eq!(b / a, Vec3::wrap(4.0, -2.5, 2.0));
+        assert_eq!(-a, Vec3::wrap(-1.0, -2.0, -3.0));
+        assert_eq!(Vec2::wrap(1.0, 2.0) + Vec2::wrap(3.0, 4.0), Vec2::wrap(4.0, 6.0));
+        assert_eq!(
+            Vec4::wrap(1.0, 2.0, 3.0, 4.0) - Vec4::wrap(1.0, 1.0, 1.0, 1.0),
+            Vec4::wrap(0.0, 1.0, 2.0, 3.0),
+        );
+    }
+
+    #[test]
+    fn scalar_operators_and_assignment() {
+        let a = Vec2::wrap(2.0, -4.0);
+        assert_eq!(a * 0.5, Vec2::wrap(1.0, -2.0));
+        assert_eq!(0.5 * a, a * 0.5);
+        assert_eq!(a / 2.0, Vec2::wrap(1.0, -2.0));
+        let mut v = Vec4::wrap(1.0, 2.0, 3.0, 4.0);
+        v += Vec4::wrap(1.0, 1.0, 1.0, 1.0);
+        v -= Vec4::wrap(0.0, 0.0, 0.0, 2.0);
+        v *= 2.0;
+        v /= 4.0;
+        assert_eq!(v, Vec4::wrap(1.0, 1.5, 2.0, 1.5));
+    }
+
+    #[test]
+    fn metric_helpers() {
+        let a = Vec3::wrap(3.0, 0.0, 4.0);
+        assert_eq!(a.dot(&Vec3::wrap(1.0, 1.0, 1.0)), 7.0);
+        assert_eq!(a.length_squared(), 25.0);
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a.distance(&Vec3::default()), 5.0);
+        assert_eq!(a.normalize(), Vec3::wrap(0.6, 0.0, 0.8));
+        assert_eq!(Vec3::default().normalize(), Vec3::default());
+        assert_eq!(a.lerp(&Vec3::default(), 0.5), Vec3::wrap(1.5, 0.0, 2.0));
+        assert_eq!(a.min(&Vec3::wrap(1.0, 1.0, 5.0)), Vec3::wrap(1.0, 0.0, 4.0));
+        assert_eq!(a.max(&Vec3::wrap(1.0, 1.0, 5.0)), Vec3::wrap(3.0, 1.0, 5.0));
+    }
+}
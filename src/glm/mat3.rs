@@ -0,0 +1,143 @@
+use std::ops::Deref;
+use super::Mat4;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Mat3 {
+    data: [f32; 9],
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self {
+            data: [
+                1.0, 0.0, 0.0,
+                0.0, 1.0, 0.0,
+                0.0, 0.0, 1.0,
+            ]
+        }
+    }
+}
+
+impl Deref for Mat3 {
+    type Target = [f32];
+
+    fn deref(&self) -> &Self::Target {
+        self.data.as_slice()
+    }
+}
+
+impl Mat3 {
+    /// Upper-left 3x3 block of a `Mat4`.
+    pub fn from_mat4(mat: &Mat4) -> Self {
+        Self {
+            data: [
+                mat[0], mat[1], mat[2],
+                mat[4], mat[5], mat[6],
+                mat[8], mat[9], mat[10],
+            ]
+        }
+    }
+
+    /// Inverse-transpose of the upper-left 3x3 block, for transforming normals.
+    /// A singular model matrix falls back to its plain 3x3 block.
+    pub fn from_mat4_normal(mat: &Mat4) -> Self {
+        let swap = Self::from_mat4(mat);
+        match swap.inverse() {
+            Some(inv) => inv.transpose(),
+            None => swap,
+        }
+    }
+}
+
+impl Mat3 {
+    pub fn transpose(&self) -> Self {
+        let a = &self.data;
+        Self {
+            data: [
+                a[0], a[3], a[6],
+                a[1], a[4], a[7],
+                a[2], a[5], a[8],
+            ]
+        }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let a = &self.data;
+        a[0] * (a[4] * a[8] - a[5] * a[7])
+            - a[3] * (a[1] * a[8] - a[2] * a[7])
+            + a[6] * (a[1] * a[5] - a[2] * a[4])
+    }
+
+    /// Returns `None` when the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let a = &self.data;
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv = 1.0 / det;
+        Some(Self {
+            data: [
+                (a[4] * a[8] - a[5] * a[7]) * inv,
+                (a[2] * a[7] - a[1] * a[8]) * inv,
+                (a[1] * a[5] - a[2] * a[4]) * inv,
+                (a[5] * a[6] - a[3] * a[8]) * inv,
+                (a[0] * a[8] - a[2] * a[6]) * inv,
+                (a[2] * a[3] - a[0] * a[5]) * inv,
+                (a[3] * a[7] - a[4] * a[6]) * inv,
+                (a[1] * a[6] - a[0] * a[7]) * inv,
+                (a[0] * a[4] - a[1] * a[3]) * inv,
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glm::{Quat, Vec3};
+
+    fn close(a: &[f32], b: &[f32]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    #[test]
+    fn inverse_and_determinant() {
+        let m = Mat3 { data: [2.0, 0.0, 0.0, 1.0, 3.0, 0.0, 0.0, 4.0, 5.0] };
+        assert_eq!(m.determinant(), 30.0);
+        let expected = [
+            0.5, 0.0, 0.0,
+            -1.0 / 6.0, 1.0 / 3.0, 0.0,
+            2.0 / 15.0, -4.0 / 15.0, 0.2,
+        ];
+        assert!(close(&m.inverse().unwrap(), &expected));
+        assert_eq!(Mat3::default().determinant(), 1.0);
+        let singular = Mat3 { data: [1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 0.0, 1.0, 1.0] };
+        assert_eq!(singular.determinant(), 0.0);
+        assert_eq!(singular.inverse(), None);
+    }
+
+    #[test]
+    fn normal_matrix_is_the_inverse_transpose() {
+        let m = Mat4::from_trs(
+            &Vec3::wrap(4.0, -2.0, 1.0),
+            &Quat::from_euler(0.3, 0.8, -0.5),
+            &Vec3::wrap(2.0, 0.5, 3.0),
+        );
+        let expected = Mat3::from_mat4(&m.inverse().unwrap().transpose());
+        assert!(close(&Mat3::from_mat4_normal(&m), &expected));
+        // Normals stay perpendicular to stretched surfaces.
+        let tangent = m.transform_vector(&Vec3::wrap(1.0, 1.0, 0.0));
+        let n = Mat3::from_mat4_normal(&m);
+        let normal = Vec3::wrap(n[6], n[7], n[8]);
+        assert!(tangent.dot(&normal).abs() < 1e-5);
+    }
+
+    #[test]
+    fn singular_models_keep_their_block() {
+        let flat = Mat4::from_scale(&Vec3::wrap(1.0, 0.0, 2.0));
+        assert_eq!(Mat3::from_mat4_normal(&flat), Mat3::from_mat4(&flat));
+    }
+}
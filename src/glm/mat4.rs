@@ -1,7 +1,21 @@
 use std::f32::consts::PI;
-use std::ops::Deref;
-use super::Vec3;
+use std::ops::{Deref, Mul};
+use super::{Quat, Vec3, Vec4};
+#[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+use super::scalar as kernel;
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+use super::simd as kernel;
 
+/// 4x4 matrix stored column-major, `data[12..15]` holds the translation,
+/// matching the layout `uniformMatrix4fv` expects without transposing.
+///
+/// Column vectors are used throughout, so `a * b` applies `b` first.
+/// The in-place methods (`scale`, `translate`, `rotate`, `perspective`, ...)
+/// post-multiply: `m.translate(v)` is `m = m * Mat4::from_translation(v)`,
+/// while `a.multiply(&b)` pre-multiplies: `a = b * a`.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Mat4 {
     data: [f32; 16],
 }
@@ -33,6 +47,228 @@ impl Deref for Mat4 {
     }
 }
 
+impl Mat4 {
+    pub fn from_cols_array(data: &[f32; 16]) -> Self {
+        Self { data: *data }
+    }
+
+    pub fn to_cols_array(&self) -> [f32; 16] {
+        self.data
+    }
+
+    pub fn from_quat(quat: &Quat) -> Self {
+        let Quat { x, y, z, w } = *quat;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+        Self {
+            data: [
+                1.0 - 2.0 * (yy + zz), 2.0 * (xy + wz), 2.0 * (xz - wy), 0.0,
+                2.0 * (xy - wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + wx), 0.0,
+                2.0 * (xz + wy), 2.0 * (yz - wx), 1.0 - 2.0 * (xx + yy), 0.0,
+                0.0, 0.0, 0.0, 1.0,
+            ]
+        }
+    }
+}
+
+impl Mat4 {
+    pub fn from_translation(vec: &Vec3) -> Self {
+        let mut swap = Self::default();
+        swap.data[12] = vec.x;
+        swap.data[13] = vec.y;
+        swap.data[14] = vec.z;
+        swap
+    }
+
+    pub fn from_scale(vec: &Vec3) -> Self {
+        let mut swap = Self::default();
+        swap.data[0] = vec.x;
+        swap.data[5] = vec.y;
+        swap.data[10] = vec.z;
+        swap
+    }
+
+    /// Rotation about `axis` by `angle` radians.
+    pub fn from_axis_angle(axis: &Vec3, angle: f32) -> Self {
+        Self::from_quat(&Quat::from_axis_angle(axis, angle))
+    }
+
+    /// Right-handed perspective projection to GL clip space, `fovy` in radians.
+    pub fn perspective_rh(fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        let dz = near - far;
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, (far + near) / dz, -1.0,
+                0.0, 0.0, 2.0 * far * near / dz, 0.0,
+            ]
+        }
+    }
+
+    /// `perspective_rh` with the far plane at infinity.
+    pub fn perspective_infinite_rh(fovy: f32, aspect: f32, near: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, -1.0, -1.0,
+                0.0, 0.0, -2.0 * near, 0.0,
+            ]
+        }
+    }
+
+    /// `perspective_rh` with depth reversed, the near plane maps to depth 1
+    /// and the far plane to 0. Pair with `clearDepth(0)` and `GREATER`.
+    ///
+    /// Clip depth still spans `[-1, 1]` and is remapped to `[0, 1]` after
+    /// the divide. That remap throws away the float precision reverse-Z
+    /// would gain, so the depth buffer is no more accurate than with
+    /// `perspective_rh`. Use `perspective_reverse_z_zo_rh` where
+    /// `EXT_clip_control` is available.
+    pub fn perspective_reverse_z_rh(fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        let dz = far - near;
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, (far + near) / dz, -1.0,
+                0.0, 0.0, 2.0 * far * near / dz, 0.0,
+            ]
+        }
+    }
+
+    /// `perspective_reverse_z_rh` with the far plane at infinity, the same
+    /// precision loss applies.
+    pub fn perspective_infinite_reverse_z_rh(fovy: f32, aspect: f32, near: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, 1.0, -1.0,
+                0.0, 0.0, 2.0 * near, 0.0,
+            ]
+        }
+    }
+
+    /// `perspective_reverse_z_rh` for a `[0, 1]` clip depth range, as set
+    /// with `EXT_clip_control` and `ZERO_TO_ONE_EXT`. Depth reaches the
+    /// buffer without a remap and keeps the precision of reverse-Z.
+    pub fn perspective_reverse_z_zo_rh(fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        let dz = far - near;
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, near / dz, -1.0,
+                0.0, 0.0, far * near / dz, 0.0,
+            ]
+        }
+    }
+
+    /// `perspective_reverse_z_zo_rh` with the far plane at infinity.
+    pub fn perspective_infinite_reverse_z_zo_rh(fovy: f32, aspect: f32, near: f32) -> Self {
+        let f = 1.0 / (fovy * 0.5).tan();
+        Self {
+            data: [
+                f / aspect, 0.0, 0.0, 0.0,
+                0.0, f, 0.0, 0.0,
+                0.0, 0.0, 0.0, -1.0,
+                0.0, 0.0, near, 0.0,
+            ]
+        }
+    }
+
+    /// Right-handed orthographic projection to GL clip space.
+    pub fn orthographic_rh(
+        left: f32, right: f32,
+        bottom: f32, top: f32,
+        near: f32, far: f32,
+    ) -> Self {
+        let (dx, dy, dz) = (right - left, top - bottom, far - near);
+        Self {
+            data: [
+                2.0 / dx, 0.0, 0.0, 0.0,
+                0.0, 2.0 / dy, 0.0, 0.0,
+                0.0, 0.0, -2.0 / dz, 0.0,
+                -(left + right) / dx, -(top + bottom) / dy, -(near + far) / dz, 1.0,
+            ]
+        }
+    }
+
+    /// Right-handed view matrix, same as the in-place `lookat`.
+    pub fn look_at_rh(eye: &Vec3, center: &Vec3, up: &Vec3) -> Self {
+        let mut swap = Self::default();
+        swap.lookat(eye, center, up);
+        swap
+    }
+
+    /// Translation, then rotation, then scale applied to column vectors,
+    /// i.e. `T * R * S`.
+    pub fn from_trs(translation: &Vec3, rotation: &Quat, scale: &Vec3) -> Self {
+        let mut swap = Self::from_quat(&rotation.normalize());
+        for i in 0..4 {
+            swap.data[i] *= scale.x;
+            swap.data[4 + i] *= scale.y;
+            swap.data[8 + i] *= scale.z;
+        }
+        swap.data[12] = translation.x;
+        swap.data[13] = translation.y;
+        swap.data[14] = translation.z;
+        swap
+    }
+
+    /// Splits an affine matrix into translation, rotation and scale, the
+    /// inverse of `from_trs`. Shear is not representable and gets folded
+    /// into the rotation; a mirrored matrix reports a negative x scale.
+    pub fn decompose(&self) -> (Vec3, Quat, Vec3) {
+        let translation = Vec3::wrap(self.data[12], self.data[13], self.data[14]);
+        let mut scale = Vec3::wrap(
+            Vec3::wrap(self.data[0], self.data[1], self.data[2]).length(),
+            Vec3::wrap(self.data[4], self.data[5], self.data[6]).length(),
+            Vec3::wrap(self.data[8], self.data[9], self.data[10]).length(),
+        );
+        if self.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+        //
+        let mut swap = Self::default();
+        for i in 0..3 {
+            if scale.x != 0.0 {
+                swap.data[i] = self.data[i] / scale.x;
+            }
+            if scale.y != 0.0 {
+                swap.data[4 + i] = self.data[4 + i] / scale.y;
+            }
+            if scale.z != 0.0 {
+                swap.data[8 + i] = self.data[8 + i] / scale.z;
+            }
+        }
+        (translation, Quat::from_mat4(&swap), scale)
+    }
+
+    /// Multiplies by `(x, y, z, 1)` and divides by the resulting `w`.
+    pub fn transform_point(&self, point: &Vec3) -> Vec3 {
+        let v = self * point.extend(1.0);
+        if v.w != 0.0 && v.w != 1.0 {
+            v.truncate() / v.w
+        } else {
+            v.truncate()
+        }
+    }
+
+    /// Multiplies by `(x, y, z, 0)`, ignoring the translation.
+    pub fn transform_vector(&self, vec: &Vec3) -> Vec3 {
+        (self * vec.extend(0.0)).truncate()
+    }
+}
+
 impl Mat4 {
     pub fn identity(&mut self) {
         self.data.fill(0.0);
@@ -92,6 +328,12 @@ impl Mat4 {
         }
     }
 
+    pub fn rotate_quat(&mut self, quat: &Quat) {
+        let mut swap = Mat4::from_quat(&quat.normalize());
+        swap.multiply(self);
+        self.data.copy_from_slice(swap.data.as_slice());
+    }
+
     pub fn ortho(&mut self, l: f32, t: f32, r: f32, b: f32, n: f32, f: f32) {
         let (dx, dy, dz) = (r - l, t - b, f - n);
         if dz != 0.0 && dy != 0.0 && dz != 0.0 {
@@ -147,73 +389,321 @@ impl Mat4 {
     }
 
     pub fn lookat(&mut self, eye: &Vec3, center: &Vec3, up: &Vec3) {
-        let mut tmt = [0f32; 9];
-        //
-        tmt[6] = eye.x - center.x;
-        tmt[7] = eye.y - center.y;
-        tmt[8] = eye.z - center.z;
-        let len = (tmt[6] * tmt[6] + tmt[7] * tmt[7] + tmt[8] * tmt[8]).sqrt();
-        if len != 0.0 {
-            tmt[6] /= len;
-            tmt[7] /= len;
-            tmt[8] /= len;
-        }
+        let z = (*eye - *center).normalize();
+        let x = up.cross(&z).normalize();
+        let y = z.cross(&x).normalize();
         //
-        tmt[0] = up.y * tmt[8] - up.z * tmt[7];
-        tmt[1] = up.z * tmt[6] - up.x * tmt[8];
-        tmt[2] = up.x * tmt[7] - up.y * tmt[6];
-        let len = (tmt[0] * tmt[0] + tmt[1] * tmt[1] + tmt[2] * tmt[2]).sqrt();
-        if len != 0.0 {
-            tmt[0] /= len;
-            tmt[1] /= len;
-            tmt[2] /= len;
-        }
-        //
-        tmt[3] = tmt[7] * tmt[2] - tmt[8] * tmt[1];
-        tmt[4] = tmt[8] * tmt[0] - tmt[6] * tmt[2];
-        tmt[5] = tmt[6] * tmt[1] - tmt[7] * tmt[0];
-        let len = (tmt[3] * tmt[3] + tmt[4] * tmt[4] + tmt[5] * tmt[5]).sqrt();
-        if len != 0.0 {
-            tmt[3] /= len;
-            tmt[4] /= len;
-            tmt[5] /= len;
-        }
-        //
-        self.data[0] = tmt[0];
-        self.data[1] = tmt[3];
-        self.data[2] = tmt[6];
+        self.data[0] = x.x;
+        self.data[1] = y.x;
+        self.data[2] = z.x;
         self.data[3] = 0.0;
-        self.data[4] = tmt[1];
-        self.data[5] = tmt[4];
-        self.data[6] = tmt[7];
+        self.data[4] = x.y;
+        self.data[5] = y.y;
+        self.data[6] = z.y;
         self.data[7] = 0.0;
-        self.data[8] = tmt[2];
-        self.data[9] = tmt[5];
-        self.data[10] = tmt[8];
+        self.data[8] = x.z;
+        self.data[9] = y.z;
+        self.data[10] = z.z;
         self.data[11] = 0.0;
-        self.data[12] = -(tmt[0] * eye.x + tmt[1] * eye.y + tmt[2] * eye.z);
-        self.data[13] = -(tmt[3] * eye.x + tmt[4] * eye.y + tmt[5] * eye.z);
-        self.data[14] = -(tmt[6] * eye.x + tmt[7] * eye.y + tmt[8] * eye.z);
+        self.data[12] = -x.dot(eye);
+        self.data[13] = -y.dot(eye);
+        self.data[14] = -z.dot(eye);
         self.data[15] = 1.0;
     }
 
+    pub fn transpose(&self) -> Self {
+        Self { data: kernel::transpose(&self.data) }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let a = &self.data;
+        let b00 = a[0] * a[5] - a[1] * a[4];
+        let b01 = a[0] * a[6] - a[2] * a[4];
+        let b02 = a[0] * a[7] - a[3] * a[4];
+        let b03 = a[1] * a[6] - a[2] * a[5];
+        let b04 = a[1] * a[7] - a[3] * a[5];
+        let b05 = a[2] * a[7] - a[3] * a[6];
+        let b06 = a[8] * a[13] - a[9] * a[12];
+        let b07 = a[8] * a[14] - a[10] * a[12];
+        let b08 = a[8] * a[15] - a[11] * a[12];
+        let b09 = a[9] * a[14] - a[10] * a[13];
+        let b10 = a[9] * a[15] - a[11] * a[13];
+        let b11 = a[10] * a[15] - a[11] * a[14];
+        b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06
+    }
+
+    /// Returns `None` when the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        kernel::inverse(&self.data).map(|data| Self { data })
+    }
+
     pub fn multiply(&mut self, rhs: &Self) {
-        for i in 0..4 {
-            let m: usize = i * 4;
-            //
-            let x = self.data[m + 0] * rhs.data[0] + self.data[m + 1] * rhs.data[4]
-                + self.data[m + 2] * rhs.data[8] + self.data[m + 3] * rhs.data[12];
-            let y = self.data[m + 0] * rhs.data[1] + self.data[m + 1] * rhs.data[5]
-                + self.data[m + 2] * rhs.data[9] + self.data[m + 3] * rhs.data[13];
-            let z = self.data[m + 0] * rhs.data[2] + self.data[m + 1] * rhs.data[6]
-                + self.data[m + 2] * rhs.data[10] + self.data[m + 3] * rhs.data[14];
-            let w = self.data[m + 0] * rhs.data[3] + self.data[m + 1] * rhs.data[7]
-                + self.data[m + 2] * rhs.data[11] + self.data[m + 3] * rhs.data[15];
-            //
-            self.data[m + 0] = x;
-            self.data[m + 1] = y;
-            self.data[m + 2] = z;
-            self.data[m + 3] = w;
+        kernel::multiply(&mut self.data, &rhs.data);
+    }
+}
+
+impl Mul<&Mat4> for &Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: &Mat4) -> Mat4 {
+        let mut swap = rhs.clone();
+        swap.multiply(self);
+        swap
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Mat4 {
+        &self * &rhs
+    }
+}
+
+impl Mul<Vec4> for &Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, rhs: Vec4) -> Vec4 {
+        let [x, y, z, w] = kernel::transform(&self.data, [rhs.x, rhs.y, rhs.z, rhs.w]);
+        Vec4 { x, y, z, w }
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, rhs: Vec4) -> Vec4 {
+        &self * rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f32], b: &[f32]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4)
+    }
+
+    fn close3(a: Vec3, b: Vec3) -> bool {
+        close(&[a.x, a.y, a.z], &[b.x, b.y, b.z])
+    }
+
+    #[test]
+    fn lookat_moves_eye_to_origin_facing_negative_z() {
+        let eye = Vec3::wrap(3.0, 4.0, 5.0);
+        let center = Vec3::wrap(1.0, -2.0, 0.5);
+        let up = Vec3::wrap(0.0, 1.0, 0.0);
+        let mut view = Mat4::default();
+        view.lookat(&eye, &center, &up);
+        assert!(close3(view.transform_point(&eye), Vec3::default()));
+        let forward = view.transform_point(&center);
+        assert!(close3(forward, Vec3::wrap(0.0, 0.0, -(center - eye).length())));
+        // Up stays in the upper half of the view.
+        assert!(view.transform_vector(&up).y > 0.0);
+        // The rotation part is orthonormal.
+        let rotation = view.transform_vector(&Vec3::wrap(1.0, 2.0, 3.0));
+        assert!((rotation.length() - 14f32.sqrt()).abs() < 1e-4);
+        assert_eq!(view, Mat4::look_at_rh(&eye, &center, &up));
+    }
+
+    fn round_trip(t: Vec3, r: Quat, s: Vec3) -> (Vec3, Quat, Vec3) {
+        let m = Mat4::from_trs(&t, &r, &s);
+        let (t2, r2, s2) = m.decompose();
+        assert!(close(&Mat4::from_trs(&t2, &r2, &s2), &m), "{:?} does not rebuild", m);
+        (t2, r2, s2)
+    }
+
+    /// Equal rotations, `q` and `-q` included.
+    fn same_rotation(a: Quat, b: Quat) -> bool {
+        (a.dot(&b).abs() - 1.0).abs() < 1e-4
+    }
+
+    #[test]
+    fn decompose_inverts_from_trs() {
+        let t = Vec3::wrap(1.0, -2.0, 3.5);
+        let r = Quat::from_axis_angle(&Vec3::wrap(1.0, 2.0, -0.5).normalize(), 1.2);
+        let s = Vec3::wrap(2.0, 0.5, 3.0);
+        let (t2, r2, s2) = round_trip(t, r, s);
+        assert!(close3(t2, t) && close3(s2, s));
+        assert!(same_rotation(r2, r));
+        // Identity and pure rotation.
+        let (t2, r2, s2) = round_trip(Vec3::default(), Quat::default(), Vec3::wrap(1.0, 1.0, 1.0));
+        assert!(close3(t2, Vec3::default()) && close3(s2, Vec3::wrap(1.0, 1.0, 1.0)));
+        assert!(same_rotation(r2, Quat::default()));
+    }
+
+    #[test]
+    fn decompose_reports_mirroring_on_x() {
+        let t = Vec3::wrap(-4.0, 0.0, 2.0);
+        let r = Quat::from_axis_angle(&Vec3::wrap(0.0, 1.0, 0.0), 0.7);
+        let s = Vec3::wrap(-2.0, 3.0, 1.5);
+        let (t2, r2, s2) = round_trip(t, r, s);
+        assert!(close3(t2, t) && close3(s2, s));
+        assert!(same_rotation(r2, r));
+        // A flip on another axis is folded into x and a rotation instead.
+        let (_, _, s2) = round_trip(t, r, Vec3::wrap(2.0, -3.0, 1.5));
+        assert!(close3(s2, Vec3::wrap(-2.0, 3.0, 1.5)));
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let data: [f32; 16] = std::array::from_fn(|i| i as f32);
+        let t = Mat4::from_cols_array(&data).transpose();
+        assert_eq!(&t[..4], &[0.0, 4.0, 8.0, 12.0]);
+        assert_eq!(&t[12..], &[3.0, 7.0, 11.0, 15.0]);
+        assert_eq!(t.transpose(), Mat4::from_cols_array(&data));
+    }
+}
+
+/// Property checks over pseudo-random inputs: each in-place method must
+/// agree with the value constructor it is documented to multiply by.
+#[cfg(test)]
+mod properties {
+    use super::*;
+
+    const CASES: usize = 256;
+
+    /// xorshift32, deterministic so failures reproduce.
+    struct Rng(u32);
+
+    impl Rng {
+        fn float(&mut self, min: f32, max: f32) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            min + (max - min) * (self.0 as f32 / u32::MAX as f32)
+        }
+
+        fn vec3(&mut self) -> Vec3 {
+            Vec3::wrap(self.float(-2.0, 2.0), self.float(-2.0, 2.0), self.float(-2.0, 2.0))
+        }
+
+        fn mat4(&mut self) -> Mat4 {
+            let mut data = [0.0; 16];
+            data.iter_mut().for_each(|d| *d = self.float(-2.0, 2.0));
+            Mat4::from_cols_array(&data)
+        }
+    }
+
+    fn assert_close(a: &Mat4, b: &Mat4, case: usize) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() <= 1e-4 * (1.0 + x.abs().max(y.abs())), "case {}: {:?} != {:?}", case, a, b);
+        }
+    }
+
+    #[test]
+    fn multiply_pre_multiplies() {
+        let mut rng = Rng(0x9E37_79B9);
+        for case in 0..CASES {
+            let (a, b) = (rng.mat4(), rng.mat4());
+            let mut m = a.clone();
+            m.multiply(&b);
+            assert_close(&m, &(&b * &a), case);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn translate_and_scale_post_multiply() {
+        let mut rng = Rng(0x1234_5678);
+        for case in 0..CASES {
+            let (a, v) = (rng.mat4(), rng.vec3());
+            let mut m = a.clone();
+            m.translate(&v);
+            assert_close(&m, &(&a * &Mat4::from_translation(&v)), case);
+            let mut m = a.clone();
+            m.scale(&v);
+            assert_close(&m, &(&a * &Mat4::from_scale(&v)), case);
+        }
+    }
+
+    #[test]
+    fn rotate_matches_axis_angle() {
+        let mut rng = Rng(0xDEAD_BEEF);
+        for case in 0..CASES {
+            let (a, axis) = (rng.mat4(), rng.vec3());
+            let degrees = rng.float(-360.0, 360.0);
+            let mut m = a.clone();
+            m.rotate(degrees, &axis);
+            assert_close(&m, &(&a * &Mat4::from_axis_angle(&axis.normalize(), degrees.to_radians())), case);
+        }
+    }
+
+    #[test]
+    fn projections_match_in_place_versions() {
+        let mut rng = Rng(0x0BAD_F00D);
+        for case in 0..CASES {
+            let a = rng.mat4();
+            let (fovy, aspect) = (rng.float(10.0, 120.0), rng.float(0.5, 2.0));
+            let (near, far) = (rng.float(0.1, 1.0), rng.float(2.0, 100.0));
+            let mut m = a.clone();
+            m.perspective(fovy, aspect, near, far);
+            assert_close(&m, &(&a * &Mat4::perspective_rh(fovy.to_radians(), aspect, near, far)), case);
+            let (l, r, b, t) = (rng.float(-3.0, -1.0), rng.float(1.0, 3.0), rng.float(-3.0, -1.0), rng.float(1.0, 3.0));
+            let mut m = a.clone();
+            m.ortho(l, t, r, b, near, far);
+            assert_close(&m, &(&a * &Mat4::orthographic_rh(l, r, b, t, near, far)), case);
+        }
+    }
+
+    #[test]
+    fn reverse_z_maps_near_to_one_and_far_to_zero() {
+        let (fovy, aspect, near, far) = (1.0, 1.5, 0.5, 40.0);
+        // Window depth of an eye-space depth, GL clip ranges get remapped.
+        let depth = |m: &Mat4, z: f32, zero_to_one: bool| {
+            let clip = m * Vec4::wrap(0.0, 0.0, -z, 1.0);
+            let ndc = clip.z / clip.w;
+            if zero_to_one { ndc } else { (ndc + 1.0) * 0.5 }
+        };
+        let finite = [
+            (Mat4::perspective_reverse_z_rh(fovy, aspect, near, far), false),
+            (Mat4::perspective_reverse_z_zo_rh(fovy, aspect, near, far), true),
+        ];
+        for (m, zo) in &finite {
+            assert!((depth(m, near, *zo) - 1.0).abs() < 1e-5);
+            assert!(depth(m, far, *zo).abs() < 1e-5);
+            assert!(depth(m, 2.0, *zo) > depth(m, 3.0, *zo));
+        }
+        let infinite = [
+            (Mat4::perspective_infinite_reverse_z_rh(fovy, aspect, near), false),
+            (Mat4::perspective_infinite_reverse_z_zo_rh(fovy, aspect, near), true),
+        ];
+        for (m, zo) in &infinite {
+            assert!((depth(m, near, *zo) - 1.0).abs() < 1e-5);
+            assert!(depth(m, 1e6, *zo) < 1e-5);
+        }
+        // Without the remap, distant depths keep their float precision.
+        let zo = &infinite[1].0;
+        assert_ne!(depth(zo, 1000.0, true), depth(zo, 1000.1, true));
+    }
+
+    #[test]
+    fn products_compose_on_vectors() {
+        let mut rng = Rng(0xC0FF_EE11);
+        for _ in 0..CASES {
+            let (a, b, v) = (rng.mat4(), rng.mat4(), rng.vec3().extend(1.0));
+            let lhs = &(&a * &b) * v;
+            let rhs = &a * (&b * v);
+            for (x, y) in [(lhs.x, rhs.x), (lhs.y, rhs.y), (lhs.z, rhs.z), (lhs.w, rhs.w)] {
+                assert!((x - y).abs() <= 1e-3 * (1.0 + x.abs()));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_a_flat_column_major_array() {
+        let m = Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 3.0));
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, "[1.0,0.0,0.0,0.0,0.0,1.0,0.0,0.0,0.0,0.0,1.0,0.0,1.0,2.0,3.0,1.0]");
+        assert_eq!(serde_json::from_str::<Mat4>(&json).unwrap(), m);
+        let rotation = Quat::from_euler(0.3, -1.2, 2.5);
+        let m = Mat4::from_trs(&Vec3::wrap(-4.0, 0.5, 9.0), &rotation, &Vec3::wrap(2.0, 1.0, 0.25));
+        let back: Mat4 = serde_json::from_str(&serde_json::to_string(&m).unwrap()).unwrap();
+        assert_eq!(back, m);
+    }
+}
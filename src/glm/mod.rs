@@ -1,5 +1,38 @@
+#[macro_use]
+mod macros;
+mod aabb;
+mod color;
+mod convert;
+mod curve;
+mod frustum;
+#[cfg(any(test, all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+mod lanes;
+mod mat3;
 mod mat4;
+mod plane;
+mod project;
+mod quat;
+mod ray;
+#[cfg_attr(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"), allow(dead_code))]
+mod scalar;
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
+mod sphere;
+mod vec2;
 mod vec3;
+mod vec4;
 
+pub use aabb::Aabb;
+pub use color::Color;
+pub use curve::{ArcLength, CatmullRom, CubicBezier, Curve, Hermite};
+pub use frustum::Frustum;
+pub use mat3::Mat3;
 pub use mat4::Mat4;
+pub use plane::Plane;
+pub use project::{screen_ray, unproject, world_to_screen};
+pub use quat::Quat;
+pub use ray::Ray;
+pub use sphere::Sphere;
+pub use vec2::Vec2;
 pub use vec3::Vec3;
+pub use vec4::Vec4;
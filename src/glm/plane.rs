@@ -0,0 +1,49 @@
+use super::Vec3;
+
+/// Plane satisfying `normal.dot(p) + d == 0`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plane {
+    pub normal: Vec3,
+    pub d: f32,
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self { normal: Vec3::wrap(0.0, 1.0, 0.0), d: 0.0 }
+    }
+}
+
+impl Plane {
+    pub fn wrap(normal: Vec3, d: f32) -> Self {
+        Self { normal, d }
+    }
+
+    pub fn from_point_normal(point: &Vec3, normal: &Vec3) -> Self {
+        let normal = normal.normalize();
+        Self { normal, d: -normal.dot(point) }
+    }
+
+    /// Counter-clockwise points face the normal.
+    pub fn from_points(a: &Vec3, b: &Vec3, c: &Vec3) -> Self {
+        let normal = (*b - *a).cross(&(*c - *a));
+        Self::from_point_normal(a, &normal)
+    }
+}
+
+impl Plane {
+    /// Scales the equation so that `normal` has unit length.
+    pub fn normalize(&self) -> Self {
+        let len = self.normal.length();
+        if len != 0.0 {
+            Self { normal: self.normal / len, d: self.d / len }
+        } else {
+            *self
+        }
+    }
+
+    /// Signed distance, positive on the side the normal points to.
+    pub fn distance(&self, point: &Vec3) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+}
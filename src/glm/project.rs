@@ -0,0 +1,152 @@
+use super::{Mat4, Ray, Vec3, Vec4};
+
+// Screen coordinates are canvas pixels with the origin at the top-left
+// corner, `viewport` is `(x, y, width, height)` in the same space.
+
+/// Maps a screen position and a depth in `[0, 1]` back to world space.
+pub fn unproject(
+    screen: &Vec3,
+    viewport: &Vec4,
+    pro: &Mat4,
+    view: &Mat4,
+) -> Option<Vec3> {
+    let inv = (pro * view).inverse()?;
+    let ndc = Vec4::wrap(
+        (screen.x - viewport.x) / viewport.z * 2.0 - 1.0,
+        1.0 - (screen.y - viewport.y) / viewport.w * 2.0,
+        screen.z * 2.0 - 1.0,
+        1.0,
+    );
+    let world = &inv * ndc;
+    if world.w == 0.0 {
+        return None;
+    }
+    Some(world.truncate() / world.w)
+}
+
+/// World-space ray through the given pixel with a normalized direction.
+/// Perspective rays start at the eye, orthographic ones on the near plane.
+/// Works with infinite and reverse-Z projections, as only depths strictly
+/// inside the clip volume are unprojected.
+pub fn screen_ray(
+    x: f32,
+    y: f32,
+    viewport: &Vec4,
+    pro: &Mat4,
+    view: &Mat4,
+) -> Option<Ray> {
+    let inv_pro = pro.inverse()?;
+    let inv_view = view.inverse()?;
+    let ndc_x = (x - viewport.x) / viewport.z * 2.0 - 1.0;
+    let ndc_y = 1.0 - (y - viewport.y) / viewport.w * 2.0;
+    let eye = |z: f32| {
+        let v = &inv_pro * Vec4::wrap(ndc_x, ndc_y, z, 1.0);
+        v.truncate() / v.w
+    };
+    // Eye space looks down -Z, whichever way the depth range runs.
+    let (a, b) = (eye(-0.5), eye(0.5));
+    let mut dir = b - a;
+    if dir.z > 0.0 {
+        dir = -dir;
+    }
+    let origin = if pro[11] != 0.0 {
+        Vec3::default()
+    } else {
+        let (n, f) = (eye(-1.0), eye(1.0));
+        if n.z > f.z { n } else { f }
+    };
+    Some(Ray::wrap(
+        inv_view.transform_point(&origin),
+        inv_view.transform_vector(&dir).normalize(),
+    ))
+}
+
+/// Projects a world position to screen pixels, `z` holds the depth in
+/// `[0, 1]`. Returns `None` for points behind the camera.
+pub fn world_to_screen(
+    point: &Vec3,
+    viewport: &Vec4,
+    pro: &Mat4,
+    view: &Mat4,
+) -> Option<Vec3> {
+    let clip = &(pro * view) * point.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    Some(Vec3::wrap(
+        viewport.x + (ndc.x + 1.0) * 0.5 * viewport.z,
+        viewport.y + (1.0 - ndc.y) * 0.5 * viewport.w,
+        (ndc.z + 1.0) * 0.5,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close3(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-3
+    }
+
+    fn camera() -> (Vec4, Mat4, Mat4) {
+        let viewport = Vec4::wrap(0.0, 0.0, 360.0, 480.0);
+        let pro = Mat4::perspective_rh(45f32.to_radians(), 0.75, 0.1, 100.0);
+        let view = Mat4::look_at_rh(&Vec3::wrap(2.0, 3.0, 6.0), &Vec3::default(), &Vec3::wrap(0.0, 1.0, 0.0));
+        (viewport, pro, view)
+    }
+
+    #[test]
+    fn world_to_screen_and_back() {
+        let (viewport, pro, view) = camera();
+        let origin = world_to_screen(&Vec3::default(), &viewport, &pro, &view).unwrap();
+        // The eye looks at the origin, so it lands mid-canvas.
+        assert!(close3(Vec3::wrap(origin.x, origin.y, 0.0), Vec3::wrap(180.0, 240.0, 0.0)));
+        assert!(origin.z > 0.0 && origin.z < 1.0);
+        for point in [Vec3::wrap(1.0, -0.5, 0.25), Vec3::wrap(-2.0, 1.0, -3.0)] {
+            let screen = world_to_screen(&point, &viewport, &pro, &view).unwrap();
+            assert!(close3(unproject(&screen, &viewport, &pro, &view).unwrap(), point));
+        }
+        // Up in the world is up on the canvas, whose y grows downwards.
+        let above = world_to_screen(&Vec3::wrap(0.0, 1.0, 0.0), &viewport, &pro, &view).unwrap();
+        assert!(above.y < origin.y);
+        assert_eq!(world_to_screen(&Vec3::wrap(4.0, 6.0, 12.0), &viewport, &pro, &view), None);
+    }
+
+    #[test]
+    fn screen_ray_passes_through_the_pixel() {
+        let (viewport, _, view) = camera();
+        let eye = Vec3::wrap(2.0, 3.0, 6.0);
+        let fovy = 45f32.to_radians();
+        let projections = [
+            Mat4::perspective_rh(fovy, 0.75, 0.1, 100.0),
+            Mat4::perspective_infinite_rh(fovy, 0.75, 0.1),
+            Mat4::perspective_reverse_z_rh(fovy, 0.75, 0.1, 100.0),
+            Mat4::perspective_infinite_reverse_z_rh(fovy, 0.75, 0.1),
+            Mat4::perspective_reverse_z_zo_rh(fovy, 0.75, 0.1, 100.0),
+            Mat4::perspective_infinite_reverse_z_zo_rh(fovy, 0.75, 0.1),
+        ];
+        for pro in &projections {
+            let center = screen_ray(180.0, 240.0, &viewport, pro, &view).unwrap();
+            assert!(close3(center.origin, eye));
+            assert!(close3(center.direction, (-eye).normalize()));
+            // Any point along the ray lands back on its pixel.
+            let ray = screen_ray(50.0, 400.0, &viewport, pro, &view).unwrap();
+            let hit = world_to_screen(&ray.at(5.0), &viewport, pro, &view).unwrap();
+            assert!(close3(Vec3::wrap(hit.x, hit.y, 0.0), Vec3::wrap(50.0, 400.0, 0.0)));
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let viewport = Vec4::wrap(0.0, 0.0, 200.0, 100.0);
+        let pro = Mat4::orthographic_rh(-4.0, 4.0, -2.0, 2.0, 1.0, 10.0);
+        let view = Mat4::default();
+        let a = screen_ray(0.0, 0.0, &viewport, &pro, &view).unwrap();
+        let b = screen_ray(200.0, 100.0, &viewport, &pro, &view).unwrap();
+        assert!(close3(a.origin, Vec3::wrap(-4.0, 2.0, -1.0)));
+        assert!(close3(b.origin, Vec3::wrap(4.0, -2.0, -1.0)));
+        assert!(close3(a.direction, Vec3::wrap(0.0, 0.0, -1.0)));
+        assert!(close3(b.direction, a.direction));
+    }
+}
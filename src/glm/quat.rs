@@ -0,0 +1,287 @@
+use std::ops::{Mul, Neg};
+use super::{Mat4, Vec3};
+
+/// Unit quaternion describing a rotation, angles are in radians.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0, z: 0.0, w: 1.0 }
+    }
+}
+
+impl Quat {
+    pub fn wrap(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn from_axis_angle(axis: &Vec3, angle: f32) -> Self {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Self {
+            x: axis.x * sin,
+            y: axis.y * sin,
+            z: axis.z * sin,
+            w: cos,
+        }
+    }
+
+    /// Rotates about X first, then Y, then Z.
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        let qx = Self::from_axis_angle(&Vec3::wrap(1.0, 0.0, 0.0), x);
+        let qy = Self::from_axis_angle(&Vec3::wrap(0.0, 1.0, 0.0), y);
+        let qz = Self::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), z);
+        qz * qy * qx
+    }
+
+    /// Extracts the rotation of a matrix without scale or shear.
+    pub fn from_mat4(mat: &Mat4) -> Self {
+        let (m00, m10, m20) = (mat[0], mat[1], mat[2]);
+        let (m01, m11, m21) = (mat[4], mat[5], mat[6]);
+        let (m02, m12, m22) = (mat[8], mat[9], mat[10]);
+        let trace = m00 + m11 + m22;
+        let quat = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::wrap((m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s, 0.25 * s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+            Self::wrap(0.25 * s, (m01 + m10) / s, (m02 + m20) / s, (m21 - m12) / s)
+        } else if m11 > m22 {
+            let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+            Self::wrap((m01 + m10) / s, 0.25 * s, (m12 + m21) / s, (m02 - m20) / s)
+        } else {
+            let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+            Self::wrap((m02 + m20) / s, (m12 + m21) / s, 0.25 * s, (m10 - m01) / s)
+        };
+        quat.normalize()
+    }
+}
+
+impl Quat {
+    pub fn dot(&self, rhs: &Self) -> f32 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len != 0.0 {
+            Self::wrap(self.x / len, self.y / len, self.z / len, self.w / len)
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::wrap(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let len = self.dot(self);
+        if len != 0.0 {
+            let c = self.conjugate();
+            Self::wrap(c.x / len, c.y / len, c.z / len, c.w / len)
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns the rotation axis and the angle in radians.
+    pub fn to_axis_angle(&self) -> (Vec3, f32) {
+        let q = self.normalize();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let sin = (1.0 - q.w * q.w).max(0.0).sqrt();
+        if sin > 1e-6 {
+            (Vec3::wrap(q.x / sin, q.y / sin, q.z / sin), angle)
+        } else {
+            (Vec3::wrap(1.0, 0.0, 0.0), 0.0)
+        }
+    }
+
+    /// Normalized linear interpolation along the shortest path.
+    pub fn nlerp(&self, rhs: &Self, t: f32) -> Self {
+        let rhs = if self.dot(rhs) < 0.0 { -*rhs } else { *rhs };
+        Self::wrap(
+            self.x + (rhs.x - self.x) * t,
+            self.y + (rhs.y - self.y) * t,
+            self.z + (rhs.z - self.z) * t,
+            self.w + (rhs.w - self.w) * t,
+        ).normalize()
+    }
+
+    /// Spherical linear interpolation along the shortest path.
+    pub fn slerp(&self, rhs: &Self, t: f32) -> Self {
+        let mut cos = self.dot(rhs);
+        let rhs = if cos < 0.0 {
+            cos = -cos;
+            -*rhs
+        } else {
+            *rhs
+        };
+        if cos > 0.9995 {
+            return self.nlerp(&rhs, t);
+        }
+        let theta = cos.acos();
+        let sin = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin;
+        let b = (t * theta).sin() / sin;
+        Self::wrap(
+            self.x * a + rhs.x * b,
+            self.y * a + rhs.y * b,
+            self.z * a + rhs.z * b,
+            self.w * a + rhs.w * b,
+        )
+    }
+}
+
+impl Neg for Quat {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::wrap(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        }
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        let q = Vec3::wrap(self.x, self.y, self.z);
+        let t = q.cross(&rhs) * 2.0;
+        rhs + t * self.w + q.cross(&t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f32], b: &[f32]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    fn same_rotation(a: &Quat, b: &Quat) -> bool {
+        (a.dot(b).abs() - 1.0).abs() < 1e-5
+    }
+
+    fn axis() -> Vec3 {
+        Vec3::wrap(1.0, 2.0, 3.0)
+    }
+
+    #[test]
+    fn slerp() {
+        let a = Quat::from_axis_angle(&axis(), 0.4);
+        let b = Quat::from_axis_angle(&axis(), 1.6);
+        assert!(same_rotation(&a.slerp(&b, 0.0), &a));
+        assert!(same_rotation(&a.slerp(&b, 1.0), &b));
+        assert!(same_rotation(&a.slerp(&b, 0.5), &Quat::from_axis_angle(&axis(), 1.0)));
+        // `-b` is the same rotation, the result must not take the long way.
+        let mid = a.slerp(&-b, 0.5);
+        assert!(same_rotation(&mid, &Quat::from_axis_angle(&axis(), 1.0)));
+        assert!(mid.dot(&a) > 0.0);
+        assert!((mid.length() - 1.0).abs() < 1e-5);
+        // Nearly equal inputs fall back to `nlerp`.
+        let near = Quat::from_axis_angle(&axis(), 0.41);
+        assert!(same_rotation(&a.slerp(&near, 0.5), &Quat::from_axis_angle(&axis(), 0.405)));
+    }
+
+    #[test]
+    fn nlerp() {
+        let a = Quat::default();
+        let b = Quat::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), 1.0);
+        assert!(same_rotation(&a.nlerp(&b, 0.0), &a));
+        assert!(same_rotation(&a.nlerp(&b, 1.0), &b));
+        // Symmetric inputs meet halfway even without the spherical weights.
+        assert!(same_rotation(&a.nlerp(&-b, 0.5), &a.slerp(&b, 0.5)));
+        assert!((a.nlerp(&b, 0.3).length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_euler_applies_x_then_y_then_z() {
+        let (x, y, z) = (0.3, -1.2, 2.5);
+        let composed = Quat::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), z)
+            * Quat::from_axis_angle(&Vec3::wrap(0.0, 1.0, 0.0), y)
+            * Quat::from_axis_angle(&Vec3::wrap(1.0, 0.0, 0.0), x);
+        let q = Quat::from_euler(x, y, z);
+        assert!(same_rotation(&q, &composed));
+        let v = Vec3::wrap(0.5, -1.0, 2.0);
+        let stepwise = Quat::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), z)
+            * (Quat::from_axis_angle(&Vec3::wrap(0.0, 1.0, 0.0), y)
+                * (Quat::from_axis_angle(&Vec3::wrap(1.0, 0.0, 0.0), x) * v));
+        let r = q * v;
+        assert!(close(&[r.x, r.y, r.z], &[stepwise.x, stepwise.y, stepwise.z]));
+    }
+
+    #[test]
+    fn matrix_round_trips() {
+        // Rotations of about pi make `w` vanish and take the other branches.
+        let cases = [
+            Quat::from_euler(0.3, -1.2, 2.5),
+            Quat::from_axis_angle(&Vec3::wrap(1.0, 0.1, 0.1), 3.1),
+            Quat::from_axis_angle(&Vec3::wrap(0.1, 1.0, 0.1), 3.1),
+            Quat::from_axis_angle(&Vec3::wrap(0.1, 0.1, 1.0), 3.1),
+            Quat::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), std::f32::consts::PI),
+        ];
+        for q in cases {
+            let back = Quat::from_mat4(&Mat4::from_quat(&q));
+            assert!(same_rotation(&back, &q), "{:?} != {:?}", back, q);
+        }
+        let q = Quat::from_axis_angle(&axis(), 0.9);
+        let (a, angle) = q.to_axis_angle();
+        assert!(close(&[a.x, a.y, a.z, angle], &{
+            let n = axis().normalize();
+            [n.x, n.y, n.z, 0.9]
+        }));
+    }
+
+    #[test]
+    fn rotates_vectors_like_its_matrix() {
+        let q = Quat::from_euler(0.3, -1.2, 2.5);
+        let v = Vec3::wrap(0.5, -1.0, 2.0);
+        let (a, b) = (q * v, Mat4::from_quat(&q).transform_vector(&v));
+        assert!(close(&[a.x, a.y, a.z], &[b.x, b.y, b.z]));
+        //
+        let mut by_angle = Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 3.0));
+        let mut by_quat = by_angle.clone();
+        by_angle.rotate(30.0, &axis());
+        by_quat.rotate_quat(&Quat::from_axis_angle(&axis(), 30f32.to_radians()));
+        assert!(close(&by_angle, &by_quat));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_named_fields() {
+        let json = serde_json::to_string(&Quat::default()).unwrap();
+        assert_eq!(json, r#"{"x":0.0,"y":0.0,"z":0.0,"w":1.0}"#);
+        let q = Quat::from_euler(0.3, -1.2, 2.5);
+        let back: Quat = serde_json::from_str(&serde_json::to_string(&q).unwrap()).unwrap();
+        assert_eq!(back, q);
+    }
+}
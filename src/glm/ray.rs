@@ -0,0 +1,180 @@
+use super::{Aabb, Plane, Sphere, Vec3};
+
+/// Half-line starting at `origin`, the intersection tests return the
+/// parameter `t` of the nearest hit in front of the origin, scaled by the
+/// length of `direction`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Default for Ray {
+    fn default() -> Self {
+        Self {
+            origin: Vec3::default(),
+            direction: Vec3::wrap(0.0, 0.0, -1.0),
+        }
+    }
+}
+
+impl Ray {
+    pub fn wrap(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction }
+    }
+}
+
+impl Ray {
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denom = plane.normal.dot(&self.direction);
+        if denom == 0.0 {
+            return None;
+        }
+        let t = -plane.distance(&self.origin) / denom;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+
+    /// Returns `0.0` when the origin is inside the box.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut near = f32::NEG_INFINITY;
+        let mut far = f32::INFINITY;
+        let axes = [
+            (self.origin.x, self.direction.x, aabb.min.x, aabb.max.x),
+            (self.origin.y, self.direction.y, aabb.min.y, aabb.max.y),
+            (self.origin.z, self.direction.z, aabb.min.z, aabb.max.z),
+        ];
+        for (o, d, min, max) in axes {
+            if d == 0.0 {
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (min - o) / d;
+            let t1 = (max - o) / d;
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+        if near <= far && far >= 0.0 {
+            Some(near.max(0.0))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `0.0` when the origin is inside the sphere.
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let oc = self.origin - sphere.center;
+        let a = self.direction.length_squared();
+        let b = oc.dot(&self.direction);
+        let c = oc.length_squared() - sphere.radius * sphere.radius;
+        if a == 0.0 {
+            return None;
+        }
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let disc = b * b - a * c;
+        if disc < 0.0 {
+            return None;
+        }
+        let t = (-b - disc.sqrt()) / a;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+
+    /// Two-sided Möller–Trumbore test.
+    pub fn intersect_triangle(&self, a: &Vec3, b: &Vec3, c: &Vec3) -> Option<f32> {
+        let e1 = *b - *a;
+        let e2 = *c - *a;
+        let p = self.direction.cross(&e2);
+        let det = e1.dot(&p);
+        // Relative to the operands so small triangles are not rejected.
+        if det.abs() <= f32::EPSILON * e1.length() * p.length() {
+            return None;
+        }
+        let inv = 1.0 / det;
+        let s = self.origin - *a;
+        let u = s.dot(&p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&e1);
+        let v = self.direction.dot(&q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = e2.dot(&q) * inv;
+        if t >= 0.0 { Some(t) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ray(origin: (f32, f32, f32), direction: (f32, f32, f32)) -> Ray {
+        Ray::wrap(Vec3::wrap(origin.0, origin.1, origin.2), Vec3::wrap(direction.0, direction.1, direction.2))
+    }
+
+    #[test]
+    fn plane() {
+        let floor = Plane::wrap(Vec3::wrap(0.0, 1.0, 0.0), 1.0);
+        assert_eq!(ray((0.0, 3.0, 0.0), (0.0, -2.0, 0.0)).intersect_plane(&floor), Some(2.0));
+        assert_eq!(ray((0.0, 3.0, 0.0), (0.0, 1.0, 0.0)).intersect_plane(&floor), None);
+        assert_eq!(ray((0.0, 3.0, 0.0), (1.0, 0.0, 0.0)).intersect_plane(&floor), None);
+        // From below, facing against the normal does not matter.
+        assert_eq!(ray((0.0, -2.0, 0.0), (0.0, 1.0, 0.0)).intersect_plane(&floor), Some(1.0));
+    }
+
+    #[test]
+    fn aabb() {
+        let unit = Aabb::wrap(Vec3::wrap(-1.0, -1.0, -1.0), Vec3::wrap(1.0, 1.0, 1.0));
+        assert_eq!(ray((-5.0, 0.0, 0.0), (1.0, 0.0, 0.0)).intersect_aabb(&unit), Some(4.0));
+        assert_eq!(ray((-5.0, 0.0, 0.0), (-1.0, 0.0, 0.0)).intersect_aabb(&unit), None);
+        assert_eq!(ray((-5.0, 2.0, 0.0), (1.0, 0.0, 0.0)).intersect_aabb(&unit), None);
+        assert_eq!(ray((-5.0, -5.0, 0.0), (1.0, 1.0, 0.0)).intersect_aabb(&unit), Some(4.0));
+        // Sliding along a face and touching an edge both count.
+        assert_eq!(ray((-5.0, 1.0, 0.0), (1.0, 0.0, 0.0)).intersect_aabb(&unit), Some(4.0));
+        assert_eq!(ray((-3.0, -1.0, 0.0), (1.0, 1.0, 0.0)).intersect_aabb(&unit), Some(2.0));
+        assert_eq!(ray((0.5, 0.0, 0.0), (0.0, 0.0, 1.0)).intersect_aabb(&unit), Some(0.0));
+    }
+
+    #[test]
+    fn sphere() {
+        let ball = Sphere::wrap(Vec3::wrap(0.0, 0.0, -5.0), 1.0);
+        assert_eq!(ray((0.0, 0.0, 0.0), (0.0, 0.0, -1.0)).intersect_sphere(&ball), Some(4.0));
+        assert_eq!(ray((0.0, 0.0, 0.0), (0.0, 0.0, 1.0)).intersect_sphere(&ball), None);
+        assert_eq!(ray((0.0, 2.0, 0.0), (0.0, 0.0, -1.0)).intersect_sphere(&ball), None);
+        assert_eq!(ray((0.0, 1.0, 0.0), (0.0, 0.0, -1.0)).intersect_sphere(&ball), Some(5.0));
+        assert_eq!(ray((0.0, 0.0, -5.5), (1.0, 0.0, 0.0)).intersect_sphere(&ball), Some(0.0));
+        assert_eq!(ray((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)).intersect_sphere(&ball), None);
+    }
+
+    #[test]
+    fn triangle() {
+        let (a, b, c) = (Vec3::wrap(0.0, 0.0, 0.0), Vec3::wrap(1.0, 0.0, 0.0), Vec3::wrap(0.0, 1.0, 0.0));
+        let hit = ray((0.25, 0.25, 1.0), (0.0, 0.0, -1.0));
+        assert_eq!(hit.intersect_triangle(&a, &b, &c), Some(1.0));
+        // Two-sided.
+        assert_eq!(hit.intersect_triangle(&a, &c, &b), Some(1.0));
+        assert_eq!(ray((0.25, 0.25, -1.0), (0.0, 0.0, -1.0)).intersect_triangle(&a, &b, &c), None);
+        assert_eq!(ray((0.75, 0.75, 1.0), (0.0, 0.0, -1.0)).intersect_triangle(&a, &b, &c), None);
+        assert_eq!(ray((0.5, 0.5, 1.0), (0.0, 0.0, -1.0)).intersect_triangle(&a, &b, &c), Some(1.0));
+        assert_eq!(ray((0.25, 0.25, 1.0), (1.0, 0.0, 0.0)).intersect_triangle(&a, &b, &c), None);
+        // Origin on the surface.
+        assert_eq!(ray((0.25, 0.25, 0.0), (0.0, 0.0, 1.0)).intersect_triangle(&a, &b, &c), Some(0.0));
+    }
+
+    #[test]
+    fn tiny_triangles_still_hit() {
+        let s = 1e-4;
+        let (a, b, c) = (Vec3::wrap(0.0, 0.0, 0.0), Vec3::wrap(s, 0.0, 0.0), Vec3::wrap(0.0, s, 0.0));
+        let t = ray((s / 4.0, s / 4.0, 1.0), (0.0, 0.0, -1.0)).intersect_triangle(&a, &b, &c);
+        assert!(t.is_some_and(|t| (t - 1.0).abs() < 1e-6), "{:?}", t);
+    }
+}
@@ -0,0 +1,80 @@
+// Portable `Mat4` kernels over column-major arrays, `simd.rs` mirrors
+// these with the exact same operation order so results are bit-identical.
+
+/// `lhs = rhs * lhs`
+pub fn multiply(lhs: &mut [f32; 16], rhs: &[f32; 16]) {
+    for i in 0..4 {
+        let m: usize = i * 4;
+        //
+        let x = lhs[m] * rhs[0] + lhs[m + 1] * rhs[4]
+            + lhs[m + 2] * rhs[8] + lhs[m + 3] * rhs[12];
+        let y = lhs[m] * rhs[1] + lhs[m + 1] * rhs[5]
+            + lhs[m + 2] * rhs[9] + lhs[m + 3] * rhs[13];
+        let z = lhs[m] * rhs[2] + lhs[m + 1] * rhs[6]
+            + lhs[m + 2] * rhs[10] + lhs[m + 3] * rhs[14];
+        let w = lhs[m] * rhs[3] + lhs[m + 1] * rhs[7]
+            + lhs[m + 2] * rhs[11] + lhs[m + 3] * rhs[15];
+        //
+        lhs[m] = x;
+        lhs[m + 1] = y;
+        lhs[m + 2] = z;
+        lhs[m + 3] = w;
+    }
+}
+
+pub fn transform(a: &[f32; 16], vec: [f32; 4]) -> [f32; 4] {
+    [
+        a[0] * vec[0] + a[4] * vec[1] + a[8] * vec[2] + a[12] * vec[3],
+        a[1] * vec[0] + a[5] * vec[1] + a[9] * vec[2] + a[13] * vec[3],
+        a[2] * vec[0] + a[6] * vec[1] + a[10] * vec[2] + a[14] * vec[3],
+        a[3] * vec[0] + a[7] * vec[1] + a[11] * vec[2] + a[15] * vec[3],
+    ]
+}
+
+pub fn transpose(a: &[f32; 16]) -> [f32; 16] {
+    let mut out = [0f32; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            out[c * 4 + r] = a[r * 4 + c];
+        }
+    }
+    out
+}
+
+pub fn inverse(a: &[f32; 16]) -> Option<[f32; 16]> {
+    let b00 = a[0] * a[5] - a[1] * a[4];
+    let b01 = a[0] * a[6] - a[2] * a[4];
+    let b02 = a[0] * a[7] - a[3] * a[4];
+    let b03 = a[1] * a[6] - a[2] * a[5];
+    let b04 = a[1] * a[7] - a[3] * a[5];
+    let b05 = a[2] * a[7] - a[3] * a[6];
+    let b06 = a[8] * a[13] - a[9] * a[12];
+    let b07 = a[8] * a[14] - a[10] * a[12];
+    let b08 = a[8] * a[15] - a[11] * a[12];
+    let b09 = a[9] * a[14] - a[10] * a[13];
+    let b10 = a[9] * a[15] - a[11] * a[13];
+    let b11 = a[10] * a[15] - a[11] * a[14];
+    let det = b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06;
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some([
+        (a[5] * b11 - a[6] * b10 + a[7] * b09) * inv,
+        (a[2] * b10 - a[1] * b11 - a[3] * b09) * inv,
+        (a[13] * b05 - a[14] * b04 + a[15] * b03) * inv,
+        (a[10] * b04 - a[9] * b05 - a[11] * b03) * inv,
+        (a[6] * b08 - a[4] * b11 - a[7] * b07) * inv,
+        (a[0] * b11 - a[2] * b08 + a[3] * b07) * inv,
+        (a[14] * b02 - a[12] * b05 - a[15] * b01) * inv,
+        (a[8] * b05 - a[10] * b02 + a[11] * b01) * inv,
+        (a[4] * b10 - a[5] * b08 + a[7] * b06) * inv,
+        (a[1] * b08 - a[0] * b10 - a[3] * b06) * inv,
+        (a[12] * b04 - a[13] * b02 + a[15] * b00) * inv,
+        (a[9] * b02 - a[8] * b04 - a[11] * b00) * inv,
+        (a[5] * b07 - a[4] * b09 - a[6] * b06) * inv,
+        (a[0] * b09 - a[1] * b07 + a[2] * b06) * inv,
+        (a[13] * b01 - a[12] * b03 - a[14] * b00) * inv,
+        (a[8] * b03 - a[9] * b01 + a[10] * b00) * inv,
+    ])
+}
@@ -0,0 +1,157 @@
+// wasm simd128 versions of the kernels in `scalar.rs`. Every lane performs
+// the same multiplications and additions in the same order as the scalar
+// code and no fused multiply-add is used, so results are bit-identical.
+
+use core::arch::wasm32::*;
+use super::lanes::*;
+
+fn load(data: &[f32], at: usize) -> v128 {
+    let lanes = &data[at..at + 4];
+    // SAFETY: `lanes` holds four floats and wasm loads need no alignment.
+    unsafe { v128_load(lanes.as_ptr() as *const v128) }
+}
+
+fn store(v: v128, out: &mut [f32], at: usize) {
+    let lanes = &mut out[at..at + 4];
+    // SAFETY: as in `load`.
+    unsafe { v128_store(lanes.as_mut_ptr() as *mut v128, v) }
+}
+
+fn gather(data: &[f32], idx: [usize; 4]) -> v128 {
+    f32x4(data[idx[0]], data[idx[1]], data[idx[2]], data[idx[3]])
+}
+
+/// `lhs = rhs * lhs`
+pub fn multiply(lhs: &mut [f32; 16], rhs: &[f32; 16]) {
+    let c0 = load(rhs, 0);
+    let c1 = load(rhs, 4);
+    let c2 = load(rhs, 8);
+    let c3 = load(rhs, 12);
+    for i in 0..4 {
+        let m: usize = i * 4;
+        let mut acc = f32x4_add(
+            f32x4_mul(f32x4_splat(lhs[m]), c0),
+            f32x4_mul(f32x4_splat(lhs[m + 1]), c1),
+        );
+        acc = f32x4_add(acc, f32x4_mul(f32x4_splat(lhs[m + 2]), c2));
+        acc = f32x4_add(acc, f32x4_mul(f32x4_splat(lhs[m + 3]), c3));
+        store(acc, lhs, m);
+    }
+}
+
+pub fn transform(a: &[f32; 16], vec: [f32; 4]) -> [f32; 4] {
+    let mut acc = f32x4_add(
+        f32x4_mul(load(a, 0), f32x4_splat(vec[0])),
+        f32x4_mul(load(a, 4), f32x4_splat(vec[1])),
+    );
+    acc = f32x4_add(acc, f32x4_mul(load(a, 8), f32x4_splat(vec[2])));
+    acc = f32x4_add(acc, f32x4_mul(load(a, 12), f32x4_splat(vec[3])));
+    let mut out = [0f32; 4];
+    store(acc, &mut out, 0);
+    out
+}
+
+pub fn transpose(a: &[f32; 16]) -> [f32; 16] {
+    let (c0, c1, c2, c3) = (load(a, 0), load(a, 4), load(a, 8), load(a, 12));
+    // Interleave pairs of columns, then pairs of those halves.
+    let t0 = i32x4_shuffle::<0, 4, 1, 5>(c0, c1);
+    let t1 = i32x4_shuffle::<2, 6, 3, 7>(c0, c1);
+    let t2 = i32x4_shuffle::<0, 4, 1, 5>(c2, c3);
+    let t3 = i32x4_shuffle::<2, 6, 3, 7>(c2, c3);
+    let mut out = [0f32; 16];
+    store(i32x4_shuffle::<0, 1, 4, 5>(t0, t2), &mut out, 0);
+    store(i32x4_shuffle::<2, 3, 6, 7>(t0, t2), &mut out, 4);
+    store(i32x4_shuffle::<0, 1, 4, 5>(t1, t3), &mut out, 8);
+    store(i32x4_shuffle::<2, 3, 6, 7>(t1, t3), &mut out, 12);
+    out
+}
+
+pub fn inverse(a: &[f32; 16]) -> Option<[f32; 16]> {
+    let mut b = [0f32; 12];
+    for i in 0..3 {
+        store(f32x4_sub(
+            f32x4_mul(gather(a, MINOR_A[i]), gather(a, MINOR_B[i])),
+            f32x4_mul(gather(a, MINOR_C[i]), gather(a, MINOR_D[i])),
+        ), &mut b, i * 4);
+    }
+    let det = b[0] * b[11] - b[1] * b[10] + b[2] * b[9]
+        + b[3] * b[8] - b[4] * b[7] + b[5] * b[6];
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+    let inv = f32x4_splat(1.0 / det);
+    let mut out = [0f32; 16];
+    for i in 0..4 {
+        let r = INV_R[i];
+        let neg = INV_NEG[i];
+        let sign = |lane: usize| if neg[lane] { -a[r[lane]] } else { a[r[lane]] };
+        let mut acc = f32x4_sub(
+            f32x4_mul(gather(a, INV_P[i]), gather(&b, INV_B1[i])),
+            f32x4_mul(gather(a, INV_Q[i]), gather(&b, INV_B2[i])),
+        );
+        acc = f32x4_add(acc, f32x4_mul(
+            f32x4(sign(0), sign(1), sign(2), sign(3)),
+            gather(&b, INV_B3[i]),
+        ));
+        store(f32x4_mul(acc, inv), &mut out, i * 4);
+    }
+    Some(out)
+}
+
+// Run with RUSTFLAGS="-C target-feature=+simd128" and `--features simd` on a
+// wasm32 target with a test runner, e.g. wasm32-wasip1 under wasmtime.
+#[cfg(test)]
+mod tests {
+    use super::super::scalar;
+
+    fn matrices() -> impl Iterator<Item = [f32; 16]> {
+        let mut state = 0x2545_f491u32;
+        (0..256).map(move |_| {
+            [0f32; 16].map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 8) as f32 / (1 << 24) as f32 * 20.0 - 10.0
+            })
+        })
+    }
+
+    fn bits(a: &[f32]) -> Vec<u32> {
+        a.iter().map(|v| v.to_bits()).collect()
+    }
+
+    #[test]
+    fn multiply_matches_scalar() {
+        let all: Vec<[f32; 16]> = matrices().collect();
+        for pair in all.windows(2) {
+            let (mut a, mut b) = (pair[0], pair[0]);
+            super::multiply(&mut a, &pair[1]);
+            scalar::multiply(&mut b, &pair[1]);
+            assert_eq!(bits(&a), bits(&b));
+        }
+    }
+
+    #[test]
+    fn transform_matches_scalar() {
+        for m in matrices() {
+            let v = [m[3], m[7], m[11], m[15]];
+            assert_eq!(bits(&super::transform(&m, v)), bits(&scalar::transform(&m, v)));
+        }
+    }
+
+    #[test]
+    fn inverse_matches_scalar() {
+        for m in matrices() {
+            let a = super::inverse(&m).map(|a| bits(&a));
+            assert_eq!(a, scalar::inverse(&m).map(|b| bits(&b)));
+        }
+        assert_eq!(super::inverse(&[0.0; 16]), None);
+    }
+
+    #[test]
+    fn transpose_matches_scalar() {
+        for m in matrices() {
+            assert_eq!(bits(&super::transpose(&m)), bits(&scalar::transpose(&m)));
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use super::Vec3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Default for Sphere {
+    fn default() -> Self {
+        Self { center: Vec3::default(), radius: 0.0 }
+    }
+}
+
+impl Sphere {
+    pub fn wrap(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+}
+
+impl Sphere {
+    pub fn contains_point(&self, point: &Vec3) -> bool {
+        (*point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, rhs: &Self) -> bool {
+        let r = self.radius + rhs.radius;
+        (rhs.center - self.center).length_squared() <= r * r
+    }
+}
@@ -0,0 +1,31 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for Vec2 {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0 }
+    }
+}
+
+impl Vec2 {
+    pub fn wrap(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl_vec_ops!(Vec2 { x, y });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_and_default() {
+        assert_eq!(Vec2::default(), Vec2::wrap(0.0, 0.0));
+        assert_eq!(Vec2::wrap(3.0, 4.0).length(), 5.0);
+    }
+}
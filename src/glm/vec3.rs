@@ -1,3 +1,7 @@
+use super::Vec4;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -14,10 +18,60 @@ impl Vec3 {
     pub fn wrap(x: f32, y: f32, z: f32) -> Self {
         Self { x, y, z }
     }
+
+    pub fn extend(&self, w: f32) -> Vec4 {
+        Vec4::wrap(self.x, self.y, self.z, w)
+    }
 }
 
+impl_vec_ops!(Vec3 { x, y, z });
+
 impl Vec3 {
-    pub fn length(&self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    pub fn cross(&self, rhs: &Self) -> Self {
+        Self {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_follows_the_right_hand_rule() {
+        let x = Vec3::wrap(1.0, 0.0, 0.0);
+        let y = Vec3::wrap(0.0, 1.0, 0.0);
+        let z = Vec3::wrap(0.0, 0.0, 1.0);
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&z), x);
+        assert_eq!(z.cross(&x), y);
+        assert_eq!(y.cross(&x), -z);
+        let a = Vec3::wrap(1.0, 2.0, 3.0);
+        let b = Vec3::wrap(-2.0, 0.5, 4.0);
+        let c = a.cross(&b);
+        assert_eq!(c.dot(&a), 0.0);
+        assert_eq!(c.dot(&b), 0.0);
+        assert_eq!(a.cross(&a), Vec3::default());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn extend_adds_w() {
+        assert_eq!(Vec3::wrap(1.0, 2.0, 3.0).extend(1.0), Vec4::wrap(1.0, 2.0, 3.0, 1.0));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_named_fields() {
+        let v = Vec3::wrap(1.0, -2.5, 3.0);
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"x":1.0,"y":-2.5,"z":3.0}"#);
+        assert_eq!(serde_json::from_str::<Vec3>(&json).unwrap(), v);
+    }
+}
@@ -0,0 +1,40 @@
+use super::Vec3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Vec4 {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0, z: 0.0, w: 0.0 }
+    }
+}
+
+impl Vec4 {
+    pub fn wrap(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn truncate(&self) -> Vec3 {
+        Vec3::wrap(self.x, self.y, self.z)
+    }
+}
+
+impl_vec_ops!(Vec4 { x, y, z, w });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_drops_w() {
+        let v = Vec4::wrap(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(v.truncate(), Vec3::wrap(1.0, 2.0, 3.0));
+        assert_eq!(v.truncate().extend(v.w), v);
+    }
+}
@@ -0,0 +1,156 @@
+use super::Json;
+
+const BYTE: u32 = 5120;
+const UNSIGNED_BYTE: u32 = 5121;
+const SHORT: u32 = 5122;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+/// Reads accessor data out of the loaded buffers of a glTF document.
+pub(super) struct Accessors<'a> {
+    pub root: &'a Json,
+    pub buffers: &'a [Vec<u8>],
+}
+
+impl<'a> Accessors<'a> {
+    /// Bytes of a buffer view and its stride, if it declares one.
+    pub fn view(&self, index: usize) -> Result<(&'a [u8], Option<usize>), String> {
+        let view = self.root.array("bufferViews").get(index)
+            .ok_or_else(|| format!("buffer view {} does not exist", index))?;
+        let context = |message: &str| format!("bufferViews[{}]: {}", index, message);
+        let buffer = view.index("buffer")
+            .and_then(|b| self.buffers.get(b))
+            .ok_or_else(|| context("missing buffer"))?;
+        let offset = view.index("byteOffset").unwrap_or(0);
+        let length = view.index("byteLength").ok_or_else(|| context("missing byteLength"))?;
+        let bytes = offset.checked_add(length)
+            .and_then(|end| buffer.get(offset..end))
+            .ok_or_else(|| context("range exceeds its buffer"))?;
+        Ok((bytes, view.index("byteStride")))
+    }
+
+    /// Elements as floats, normalized integers mapped to `[0, 1]` or
+    /// `[-1, 1]`. Returns the flattened values and the component count.
+    pub fn floats(&self, index: usize) -> Result<(Vec<f32>, usize), String> {
+        let (values, components, component_type, normalized) = self.read(index)?;
+        let scale = match component_type {
+            BYTE => 127.0,
+            UNSIGNED_BYTE => 255.0,
+            SHORT => 32767.0,
+            UNSIGNED_SHORT => 65535.0,
+            _ => 1.0,
+        };
+        let floats = values
+            .iter()
+            .map(|v| if normalized { ((v / scale) as f32).max(-1.0) } else { *v as f32 })
+            .collect();
+        Ok((floats, components))
+    }
+
+    /// A scalar accessor of unsigned integers, as used for indices.
+    pub fn indices(&self, index: usize) -> Result<Vec<u32>, String> {
+        let (values, components, component_type, _) = self.read(index)?;
+        if components != 1 || !matches!(component_type, UNSIGNED_BYTE | UNSIGNED_SHORT | UNSIGNED_INT) {
+            return Err(format!("accessors[{}]: indices must be unsigned scalars", index));
+        }
+        Ok(values.iter().map(|v| *v as u32).collect())
+    }
+
+    /// Raw component values with sparse substitutions applied, plus the
+    /// component count, component type and normalized flag.
+    fn read(&self, index: usize) -> Result<(Vec<f64>, usize, u32, bool), String> {
+        let accessor = self.root.array("accessors").get(index)
+            .ok_or_else(|| format!("accessor {} does not exist", index))?;
+        let context = |message: String| format!("accessors[{}]: {}", index, message);
+        let count = accessor.index("count").ok_or_else(|| context("missing count".to_owned()))?;
+        let component_type = accessor.index("componentType").unwrap_or(0) as u32;
+        let components = match accessor.str("type") {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") | Some("MAT2") => 4,
+            Some("MAT3") => 9,
+            Some("MAT4") => 16,
+            other => return Err(context(format!("unknown type {:?}", other))),
+        };
+        let normalized = accessor.get("normalized").and_then(Json::as_bool).unwrap_or(false);
+        // Without a buffer view the accessor is all zeros, sparse data aside.
+        let mut values = match accessor.index("bufferView") {
+            Some(view) => {
+                let offset = accessor.index("byteOffset").unwrap_or(0);
+                self.elements(view, offset, count, components, component_type).map_err(context)?
+            }
+            None => vec![0.0; count * components],
+        };
+        if let Some(sparse) = accessor.get("sparse") {
+            let substitutes = sparse.index("count").unwrap_or(0);
+            let (indices, replacements) = match (sparse.get("indices"), sparse.get("values")) {
+                (Some(i), Some(v)) => (i, v),
+                _ => return Err(context("sparse data needs indices and values".to_owned())),
+            };
+            let targets = self.elements(
+                indices.index("bufferView").ok_or_else(|| context("sparse indices need a buffer view".to_owned()))?,
+                indices.index("byteOffset").unwrap_or(0),
+                substitutes,
+                1,
+                indices.index("componentType").unwrap_or(0) as u32,
+            ).map_err(context)?;
+            let replacements = self.elements(
+                replacements.index("bufferView").ok_or_else(|| context("sparse values need a buffer view".to_owned()))?,
+                replacements.index("byteOffset").unwrap_or(0),
+                substitutes,
+                components,
+                component_type,
+            ).map_err(context)?;
+            for (target, replacement) in targets.iter().zip(replacements.chunks(components)) {
+                let target = *target as usize;
+                values.get_mut(target * components..(target + 1) * components)
+                    .ok_or_else(|| context(format!("sparse index {} out of range", target)))?
+                    .copy_from_slice(replacement);
+            }
+        }
+        Ok((values, components, component_type, normalized))
+    }
+
+    fn elements(
+        &self,
+        view: usize,
+        offset: usize,
+        count: usize,
+        components: usize,
+        component_type: u32,
+    ) -> Result<Vec<f64>, String> {
+        let size = match component_type {
+            BYTE | UNSIGNED_BYTE => 1,
+            SHORT | UNSIGNED_SHORT => 2,
+            UNSIGNED_INT | FLOAT => 4,
+            other => return Err(format!("unknown component type {}", other)),
+        };
+        let (bytes, stride) = self.view(view)?;
+        let stride = stride.unwrap_or(size * components);
+        let end = match count {
+            0 => Some(0),
+            _ => (count - 1).checked_mul(stride).and_then(|n| n.checked_add(offset)?.checked_add(size * components)),
+        };
+        if end.is_none_or(|end| end > bytes.len()) {
+            return Err(format!("{} elements do not fit buffer view {}", count, view));
+        }
+        let mut out = Vec::with_capacity(count * components);
+        for i in 0..count {
+            for j in 0..components {
+                let at = offset + i * stride + j * size;
+                let b = &bytes[at..at + size];
+                out.push(match component_type {
+                    BYTE => b[0] as i8 as f64,
+                    UNSIGNED_BYTE => b[0] as f64,
+                    SHORT => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    UNSIGNED_SHORT => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    UNSIGNED_INT => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                });
+            }
+        }
+        Ok(out)
+    }
+}
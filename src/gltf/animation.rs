@@ -0,0 +1,183 @@
+use crate::glm::{Quat, Vec3};
+use crate::scene::{NodeId, Scene};
+use super::accessor::Accessors;
+use super::Json;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Interpolation {
+    Step,
+    Linear,
+    /// Hermite spline, every keyframe stores in-tangent, value and out-tangent.
+    CubicSpline,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Property {
+    Translation,
+    Rotation,
+    Scale,
+    /// Morph target weights.
+    Weights,
+}
+
+/// Keyframes driving one property of one node, values are flattened.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Channel {
+    pub node: usize,
+    pub property: Property,
+    pub interpolation: Interpolation,
+    pub times: Vec<f32>,
+    pub values: Vec<f32>,
+}
+
+impl Channel {
+    /// Components of one sampled value.
+    pub fn width(&self) -> usize {
+        let per_key = match self.interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1,
+        };
+        self.values.len() / (self.times.len() * per_key).max(1)
+    }
+
+    /// The value at `time`, held at the first and last keyframe outside of
+    /// their range. Rotations come out normalized.
+    pub fn sample(&self, time: f32) -> Vec<f32> {
+        let width = self.width();
+        let cubic = self.interpolation == Interpolation::CubicSpline;
+        // Element `slot` of keyframe `k`, slots only differ for splines.
+        let at = |k: usize, slot: usize| -> &[f32] {
+            let i = if cubic { 3 * k + slot } else { k };
+            &self.values[i * width..(i + 1) * width]
+        };
+        let last = match self.times.len() {
+            0 => return Vec::new(),
+            n => n - 1,
+        };
+        if time <= self.times[0] {
+            return at(0, 1).to_vec();
+        }
+        if time >= self.times[last] {
+            return at(last, 1).to_vec();
+        }
+        let k = self.times.partition_point(|t| *t <= time) - 1;
+        let dt = self.times[k + 1] - self.times[k];
+        let s = (time - self.times[k]) / dt;
+        let mut out: Vec<f32> = match self.interpolation {
+            Interpolation::Step => at(k, 1).to_vec(),
+            Interpolation::Linear if self.property == Property::Rotation => {
+                let (a, b) = (at(k, 1), at(k + 1, 1));
+                let q = Quat::wrap(a[0], a[1], a[2], a[3]).slerp(&Quat::wrap(b[0], b[1], b[2], b[3]), s);
+                return vec![q.x, q.y, q.z, q.w];
+            }
+            Interpolation::Linear => {
+                at(k, 1).iter().zip(at(k + 1, 1)).map(|(a, b)| a + (b - a) * s).collect()
+            }
+            Interpolation::CubicSpline => {
+                let (s2, s3) = (s * s, s * s * s);
+                let (h00, h10) = (2.0 * s3 - 3.0 * s2 + 1.0, s3 - 2.0 * s2 + s);
+                let (h01, h11) = (-2.0 * s3 + 3.0 * s2, s3 - s2);
+                (0..width)
+                    .map(|i| {
+                        h00 * at(k, 1)[i] + h10 * dt * at(k, 2)[i]
+                            + h01 * at(k + 1, 1)[i] + h11 * dt * at(k + 1, 0)[i]
+                    })
+                    .collect()
+            }
+        };
+        if self.property == Property::Rotation {
+            let length = out.iter().map(|c| c * c).sum::<f32>().sqrt();
+            if length > 0.0 {
+                out.iter_mut().for_each(|c| *c /= length);
+            }
+        }
+        out
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Animation {
+    pub name: String,
+    pub channels: Vec<Channel>,
+}
+
+impl Animation {
+    /// Time of the last keyframe over all channels, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.channels
+            .iter()
+            .filter_map(|c| c.times.last())
+            .fold(0.0, |a, b| a.max(*b))
+    }
+
+    /// Poses the scene nodes at `time`, `nodes` maps glTF node indices as
+    /// returned by `GltfModel::instantiate`. Morph weights are skipped,
+    /// the engine has no morph targets.
+    pub fn apply(&self, time: f32, scene: &mut Scene, nodes: &[Option<NodeId>]) {
+        for channel in &self.channels {
+            let node = match nodes.get(channel.node).copied().flatten().and_then(|id| scene.node_mut(id)) {
+                Some(node) => node,
+                None => continue,
+            };
+            let v = channel.sample(time);
+            match channel.property {
+                Property::Translation => node.set_translation(Vec3::wrap(v[0], v[1], v[2])),
+                Property::Rotation => node.set_rotation(Quat::wrap(v[0], v[1], v[2], v[3])),
+                Property::Scale => node.set_scale(Vec3::wrap(v[0], v[1], v[2])),
+                Property::Weights => {}
+            }
+        }
+    }
+}
+
+impl Animation {
+    pub(super) fn parse(json: &Json, accessors: &Accessors, nodes: usize) -> Result<Self, String> {
+        let samplers = json.array("samplers");
+        let mut channels = Vec::new();
+        for (i, channel) in json.array("channels").iter().enumerate() {
+            let context = |message: String| format!("channels[{}]: {}", i, message);
+            let target = channel.get("target").ok_or_else(|| context("missing target".to_owned()))?;
+            // Channels without a node target extensions we do not know.
+            let node = match target.index("node") {
+                Some(node) if node < nodes => node,
+                Some(node) => return Err(context(format!("node {} does not exist", node))),
+                None => continue,
+            };
+            let property = match target.str("path") {
+                Some("translation") => Property::Translation,
+                Some("rotation") => Property::Rotation,
+                Some("scale") => Property::Scale,
+                Some("weights") => Property::Weights,
+                other => return Err(context(format!("unknown path {:?}", other))),
+            };
+            let sampler = channel.index("sampler")
+                .and_then(|s| samplers.get(s))
+                .ok_or_else(|| context("missing sampler".to_owned()))?;
+            let interpolation = match sampler.str("interpolation").unwrap_or("LINEAR") {
+                "STEP" => Interpolation::Step,
+                "LINEAR" => Interpolation::Linear,
+                "CUBICSPLINE" => Interpolation::CubicSpline,
+                other => return Err(context(format!("unknown interpolation '{}'", other))),
+            };
+            let input = sampler.index("input").ok_or_else(|| context("sampler has no input".to_owned()))?;
+            let output = sampler.index("output").ok_or_else(|| context("sampler has no output".to_owned()))?;
+            let (times, _) = accessors.floats(input)?;
+            let (values, _) = accessors.floats(output)?;
+            let channel = Channel { node, property, interpolation, times, values };
+            let width = match property {
+                Property::Translation | Property::Scale => 3,
+                Property::Rotation => 4,
+                Property::Weights => channel.width().max(1),
+            };
+            let keys = channel.times.len() * if interpolation == Interpolation::CubicSpline { 3 } else { 1 };
+            if channel.values.len() != keys * width {
+                return Err(context(format!("{} values do not match {} keyframes", channel.values.len(), channel.times.len())));
+            }
+            channels.push(channel);
+        }
+        Ok(Animation {
+            name: json.str("name").unwrap_or_default().to_owned(),
+            channels,
+        })
+    }
+}
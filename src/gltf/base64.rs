@@ -0,0 +1,39 @@
+/// Decodes standard base64, trailing padding is optional.
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(format!("invalid base64 character '{}'", c as char)),
+        };
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    if count >= 6 {
+        return Err("truncated base64 data".to_owned());
+    }
+    Ok(out)
+}
+
+/// Media type and payload of a `data:` URI, `None` for any other URI.
+pub fn data_uri(uri: &str) -> Option<Result<(String, Vec<u8>), String>> {
+    let rest = uri.strip_prefix("data:")?;
+    Some(match rest.split_once(',') {
+        Some((header, payload)) => match header.strip_suffix(";base64") {
+            Some(mime) => decode(payload).map(|data| (mime.to_owned(), data)),
+            None => Err(format!("data URI of type '{}' is not base64", header)),
+        },
+        None => Err("malformed data URI".to_owned()),
+    })
+}
@@ -0,0 +1,497 @@
+use crate::glm::{Mat4, Quat, Vec2, Vec3};
+use crate::obj::{Geometry, Primitive};
+use crate::scene::{NodeId, Scene};
+use super::accessor::Accessors;
+use super::{base64, Animation, GltfTexture, Image, Json, PbrMaterial, Sampler};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON: u32 = 0x4E4F_534A;
+const GLB_BIN: u32 = 0x004E_4942;
+
+/// One draw of a mesh. Strips and fans are expanded to triangle lists;
+/// missing normals are generated flat, missing UVs are zero.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfPrimitive {
+    pub geometry: Geometry,
+    pub primitive: Primitive,
+    /// Index into `GltfModel::materials`, `None` for the default material.
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfMesh {
+    pub name: String,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+/// A node of the glTF hierarchy, a `matrix` is stored decomposed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfNode {
+    pub name: String,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    pub children: Vec<usize>,
+    pub mesh: Option<usize>,
+}
+
+/// A loaded glTF 2.0 asset, objects keep their glTF indices.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct GltfModel {
+    pub meshes: Vec<GltfMesh>,
+    pub nodes: Vec<GltfNode>,
+    /// Root nodes of each scene.
+    pub scenes: Vec<Vec<usize>>,
+    pub scene: Option<usize>,
+    pub materials: Vec<PbrMaterial>,
+    pub textures: Vec<GltfTexture>,
+    pub images: Vec<Image>,
+    pub animations: Vec<Animation>,
+}
+
+impl GltfModel {
+    /// Loads a `.gltf` file. `resolve` returns the contents of an external
+    /// buffer from its URI, relative to the file; `data:` URIs are decoded
+    /// here.
+    pub fn parse(text: &str, resolve: impl FnMut(&str) -> Result<Vec<u8>, String>) -> Result<Self, String> {
+        Self::load(&Json::parse(text)?, None, resolve)
+    }
+
+    /// Loads a binary `.glb` file, whose first buffer may live in the file
+    /// itself.
+    pub fn parse_glb(bytes: &[u8], resolve: impl FnMut(&str) -> Result<Vec<u8>, String>) -> Result<Self, String> {
+        let word = |at: usize| -> Option<u32> {
+            bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        if word(0) != Some(GLB_MAGIC) {
+            return Err("GLB: not a binary glTF file".to_owned());
+        }
+        if word(4) != Some(2) {
+            return Err(format!("GLB: unsupported container version {:?}", word(4)));
+        }
+        let length = (word(8).unwrap_or(0) as usize).min(bytes.len());
+        let mut chunks = Vec::new();
+        let mut at = 12;
+        while at + 8 <= length {
+            let (size, kind) = (word(at).unwrap() as usize, word(at + 4).unwrap());
+            let data = bytes.get(at + 8..(at + 8).saturating_add(size).min(length))
+                .filter(|data| data.len() == size)
+                .ok_or_else(|| format!("GLB: chunk at byte {} is truncated", at))?;
+            chunks.push((kind, data));
+            at += 8 + size;
+        }
+        let json = match chunks.first() {
+            Some((GLB_JSON, data)) => std::str::from_utf8(data).map_err(|_| "GLB: JSON chunk is not UTF-8".to_owned())?,
+            _ => return Err("GLB: the first chunk must be JSON".to_owned()),
+        };
+        let bin = chunks.get(1).filter(|(kind, _)| *kind == GLB_BIN).map(|(_, data)| *data);
+        Self::load(&Json::parse(json)?, bin, resolve)
+    }
+
+    fn load(
+        root: &Json,
+        bin: Option<&[u8]>,
+        mut resolve: impl FnMut(&str) -> Result<Vec<u8>, String>,
+    ) -> Result<Self, String> {
+        let version = root.get("asset").and_then(|a| a.str("version")).unwrap_or_default();
+        if !version.starts_with("2.") {
+            return Err(format!("unsupported glTF version '{}'", version));
+        }
+        if let Some(required) = root.array("extensionsRequired").first() {
+            return Err(format!("required extension {:?} is not supported", required.as_str().unwrap_or_default()));
+        }
+        //
+        let mut buffers = Vec::new();
+        for (i, buffer) in root.array("buffers").iter().enumerate() {
+            let context = |message: String| format!("buffers[{}]: {}", i, message);
+            let data = match (buffer.str("uri"), bin) {
+                (Some(uri), _) => match base64::data_uri(uri) {
+                    Some(decoded) => decoded.map(|(_, data)| data),
+                    None => resolve(uri),
+                }
+                .map_err(context)?,
+                (None, Some(bin)) if i == 0 => bin.to_vec(),
+                (None, _) => return Err(context("no uri and no GLB binary chunk".to_owned())),
+            };
+            let length = buffer.index("byteLength").unwrap_or(0);
+            if data.len() < length {
+                return Err(context(format!("{} bytes, expected {}", data.len(), length)));
+            }
+            buffers.push(data);
+        }
+        let accessors = Accessors { root, buffers: &buffers };
+        //
+        let mut model = GltfModel::default();
+        for (i, image) in root.array("images").iter().enumerate() {
+            let context = |message: String| format!("images[{}]: {}", i, message);
+            model.images.push(match (image.str("uri"), image.index("bufferView")) {
+                (Some(uri), _) => match base64::data_uri(uri) {
+                    Some(decoded) => {
+                        let (mime_type, data) = decoded.map_err(context)?;
+                        Image::Embedded { mime_type, data }
+                    }
+                    None => Image::Uri(uri.to_owned()),
+                },
+                (None, Some(view)) => Image::Embedded {
+                    mime_type: image.str("mimeType").unwrap_or_default().to_owned(),
+                    data: accessors.view(view).map_err(context)?.0.to_vec(),
+                },
+                (None, None) => return Err(context("needs a uri or a buffer view".to_owned())),
+            });
+        }
+        let samplers = root.array("samplers");
+        for (i, texture) in root.array("textures").iter().enumerate() {
+            let sampler = match texture.index("sampler") {
+                Some(s) => Sampler::parse(samplers.get(s).ok_or_else(|| format!("textures[{}]: sampler {} does not exist", i, s))?),
+                None => Sampler::default(),
+            };
+            let image = texture.index("source").filter(|s| *s < model.images.len());
+            model.textures.push(GltfTexture { image, sampler });
+        }
+        for (i, material) in root.array("materials").iter().enumerate() {
+            let material = PbrMaterial::parse(material, model.textures.len())
+                .map_err(|e| format!("materials[{}]: {}", i, e))?;
+            model.materials.push(material);
+        }
+        for (i, mesh) in root.array("meshes").iter().enumerate() {
+            let mut primitives = Vec::new();
+            for (j, primitive) in mesh.array("primitives").iter().enumerate() {
+                let primitive = parse_primitive(primitive, &accessors, model.materials.len())
+                    .map_err(|e| format!("meshes[{}].primitives[{}]: {}", i, j, e))?;
+                primitives.push(primitive);
+            }
+            model.meshes.push(GltfMesh {
+                name: mesh.str("name").unwrap_or_default().to_owned(),
+                primitives,
+            });
+        }
+        //
+        let count = root.array("nodes").len();
+        let mut parented = vec![false; count];
+        for (i, node) in root.array("nodes").iter().enumerate() {
+            let context = |message: String| format!("nodes[{}]: {}", i, message);
+            let (translation, rotation, scale) = match node.floats::<16>("matrix") {
+                Some(matrix) => Mat4::from_cols_array(&matrix).decompose(),
+                None => {
+                    let t = node.floats::<3>("translation").unwrap_or([0.0; 3]);
+                    let r = node.floats::<4>("rotation").unwrap_or([0.0, 0.0, 0.0, 1.0]);
+                    let s = node.floats::<3>("scale").unwrap_or([1.0; 3]);
+                    (Vec3::wrap(t[0], t[1], t[2]), Quat::wrap(r[0], r[1], r[2], r[3]), Vec3::wrap(s[0], s[1], s[2]))
+                }
+            };
+            let mut children = Vec::new();
+            for child in node.array("children") {
+                // A node may have one parent only.
+                match child.as_f64().map(|c| c as usize).filter(|c| *c < count) {
+                    Some(c) if !parented[c] => {
+                        parented[c] = true;
+                        children.push(c);
+                    }
+                    _ => return Err(context(format!("invalid child {:?}", child))),
+                }
+            }
+            let mesh = match node.index("mesh") {
+                Some(m) if m >= model.meshes.len() => return Err(context(format!("mesh {} does not exist", m))),
+                mesh => mesh,
+            };
+            model.nodes.push(GltfNode {
+                name: node.str("name").unwrap_or_default().to_owned(),
+                translation,
+                rotation,
+                scale,
+                children,
+                mesh,
+            });
+        }
+        // With one parent each, nodes not reachable from a parentless one
+        // can only sit on a cycle.
+        let mut reached = vec![false; count];
+        let mut stack: Vec<usize> = (0..count).filter(|i| !parented[*i]).collect();
+        while let Some(i) = stack.pop() {
+            reached[i] = true;
+            stack.extend(&model.nodes[i].children);
+        }
+        if let Some(i) = reached.iter().position(|r| !r) {
+            return Err(format!("nodes[{}]: part of a cycle", i));
+        }
+        for (i, scene) in root.array("scenes").iter().enumerate() {
+            let roots = scene.array("nodes").iter()
+                .map(|n| n.as_f64().map(|n| n as usize).filter(|n| *n < count && !parented[*n]))
+                .collect::<Option<Vec<usize>>>()
+                .ok_or_else(|| format!("scenes[{}]: roots must be existing parentless nodes", i))?;
+            model.scenes.push(roots);
+        }
+        model.scene = root.index("scene").filter(|s| *s < model.scenes.len());
+        for (i, animation) in root.array("animations").iter().enumerate() {
+            let animation = Animation::parse(animation, &accessors, count)
+                .map_err(|e| format!("animations[{}].{}", i, e))?;
+            model.animations.push(animation);
+        }
+        Ok(model)
+    }
+}
+
+impl GltfModel {
+    /// Roots of the default scene, or of the first one. Without scenes
+    /// every node lacking a parent is a root.
+    pub fn roots(&self) -> Vec<usize> {
+        match self.scene.or(if self.scenes.is_empty() { None } else { Some(0) }) {
+            Some(scene) => self.scenes[scene].clone(),
+            None => {
+                let mut parented = vec![false; self.nodes.len()];
+                self.nodes.iter().flat_map(|n| &n.children).for_each(|c| parented[*c] = true);
+                (0..self.nodes.len()).filter(|i| !parented[*i]).collect()
+            }
+        }
+    }
+
+    /// Adds the hierarchy under `roots()` to `scene`, below `parent` when
+    /// given. Returns the created node for every glTF node index, meshes
+    /// are left to the caller to attach.
+    pub fn instantiate(&self, scene: &mut Scene, parent: Option<NodeId>) -> Vec<Option<NodeId>> {
+        let mut ids: Vec<Option<NodeId>> = vec![None; self.nodes.len()];
+        let mut stack: Vec<(usize, Option<NodeId>)> = self.roots().into_iter().rev().map(|r| (r, parent)).collect();
+        while let Some((index, parent)) = stack.pop() {
+            if ids[index].is_some() {
+                continue;
+            }
+            let gltf = &self.nodes[index];
+            let id = scene.add(parent);
+            let node = scene.node_mut(id).unwrap();
+            node.set_translation(gltf.translation);
+            node.set_rotation(gltf.rotation);
+            node.set_scale(gltf.scale);
+            ids[index] = Some(id);
+            stack.extend(gltf.children.iter().rev().map(|c| (*c, Some(id))));
+        }
+        ids
+    }
+}
+
+fn parse_primitive(json: &Json, accessors: &Accessors, materials: usize) -> Result<GltfPrimitive, String> {
+    let attributes = json.get("attributes").ok_or("missing attributes")?;
+    let attribute = |name: &str, components: usize| -> Result<Option<Vec<f32>>, String> {
+        let index = match attributes.index(name) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let (values, found) = accessors.floats(index)?;
+        if found != components {
+            return Err(format!("{} has {} components, expected {}", name, found, components));
+        }
+        Ok(Some(values))
+    };
+    let positions = attribute("POSITION", 3)?.ok_or("missing POSITION")?;
+    let count = positions.len() / 3;
+    let normals = attribute("NORMAL", 3)?;
+    let uvs = attribute("TEXCOORD_0", 2)?;
+    if normals.as_ref().is_some_and(|n| n.len() != positions.len())
+        || uvs.as_ref().is_some_and(|t| t.len() / 2 != count)
+    {
+        return Err("attribute counts differ".to_owned());
+    }
+    let mut indices = match json.index("indices") {
+        Some(index) => accessors.indices(index)?,
+        None => (0..count as u32).collect(),
+    };
+    if let Some(i) = indices.iter().find(|i| **i as usize >= count) {
+        return Err(format!("index {} out of range ({} vertices)", i, count));
+    }
+    let mut primitive = match json.index("mode").unwrap_or(4) {
+        0 => Primitive::Points,
+        1 => Primitive::Lines,
+        2 => Primitive::LineLoop,
+        3 => Primitive::LineStrip,
+        4 => Primitive::Triangles,
+        5 => Primitive::TriangleStrip,
+        6 => Primitive::TriangleFan,
+        other => return Err(format!("unknown mode {}", other)),
+    };
+    let corners = indices.len().saturating_sub(2);
+    match primitive {
+        Primitive::TriangleStrip => {
+            indices = (0..corners)
+                .flat_map(|i| match i % 2 {
+                    0 => [indices[i], indices[i + 1], indices[i + 2]],
+                    _ => [indices[i + 1], indices[i], indices[i + 2]],
+                })
+                .collect();
+            primitive = Primitive::Triangles;
+        }
+        Primitive::TriangleFan => {
+            indices = (0..corners).flat_map(|i| [indices[0], indices[i + 1], indices[i + 2]]).collect();
+            primitive = Primitive::Triangles;
+        }
+        Primitive::Triangles => indices.truncate(indices.len() / 3 * 3),
+        _ => {}
+    }
+    //
+    let position = |i: usize| Vec3::wrap(positions[3 * i], positions[3 * i + 1], positions[3 * i + 2]);
+    let uv = |i: usize| uvs.as_ref().map_or(Vec2::default(), |t| Vec2::wrap(t[2 * i], t[2 * i + 1]));
+    let mut geometry = Geometry::default();
+    match &normals {
+        Some(n) => {
+            for i in 0..count {
+                geometry.vertex(position(i), Vec3::wrap(n[3 * i], n[3 * i + 1], n[3 * i + 2]), uv(i));
+            }
+            geometry.indices = indices;
+        }
+        // Flat normals need a vertex per triangle corner.
+        None if primitive == Primitive::Triangles => {
+            for t in indices.chunks(3) {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| i as usize);
+                let normal = (position(b) - position(a)).cross(&(position(c) - position(a))).normalize();
+                let first = geometry.vertex(position(a), normal, uv(a));
+                geometry.vertex(position(b), normal, uv(b));
+                geometry.vertex(position(c), normal, uv(c));
+                geometry.triangle(first, first + 1, first + 2);
+            }
+        }
+        None => {
+            for i in 0..count {
+                geometry.vertex(position(i), Vec3::default(), uv(i));
+            }
+            geometry.indices = indices;
+        }
+    }
+    let material = match json.index("material") {
+        Some(m) if m >= materials => return Err(format!("material {} does not exist", m)),
+        material => material,
+    };
+    Ok(GltfPrimitive { geometry, primitive, material })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gltf::{AlphaMode, Interpolation, Property};
+
+    const TRIANGLE: &str = include_str!("samples/triangle.gltf");
+    const QUAD: &str = include_str!("samples/quad.gltf");
+    const QUAD_BIN: &[u8] = include_bytes!("samples/quad.bin");
+    const QUAD_GLB: &[u8] = include_bytes!("samples/quad.glb");
+
+    fn files(uri: &str) -> Result<Vec<u8>, String> {
+        match uri {
+            "quad.bin" => Ok(QUAD_BIN.to_vec()),
+            _ => Err(format!("no file '{}'", uri)),
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn embedded_buffer_with_sparse_positions() {
+        let model = GltfModel::parse(TRIANGLE, files).unwrap();
+        let primitive = &model.meshes[0].primitives[0];
+        let g = &primitive.geometry;
+        assert_eq!(primitive.primitive, Primitive::Triangles);
+        assert_eq!(primitive.material, None);
+        assert_eq!(g.positions[2], Vec3::wrap(0.0, 2.0, 0.0));
+        assert_eq!(g.indices, vec![0, 1, 2]);
+        // No NORMAL attribute, so flat normals facing the winding.
+        assert!(g.normals.iter().all(|n| *n == Vec3::wrap(0.0, 0.0, 1.0)));
+        assert_eq!(model.roots(), vec![0]);
+    }
+
+    #[test]
+    fn external_buffer_materials_and_nodes() {
+        let model = GltfModel::parse(QUAD, files).unwrap();
+        let primitive = &model.meshes[0].primitives[0];
+        assert_eq!(primitive.geometry.vertex_count(), 4);
+        assert_eq!(primitive.geometry.indices, vec![0, 1, 2, 2, 3, 0]);
+        assert_eq!(primitive.geometry.uvs[3], Vec2::wrap(0.0, 0.0));
+        assert_eq!(primitive.geometry.normals[1], Vec3::wrap(0.0, 0.0, 1.0));
+
+        let material = &model.materials[primitive.material.unwrap()];
+        assert_eq!(material.name, "checker");
+        assert_eq!(material.base_color.g, 0.5);
+        assert_eq!(material.base_color_texture.unwrap().texture, 0);
+        assert_eq!((material.metallic, material.roughness), (0.0, 0.5));
+        assert_eq!(material.alpha_mode, AlphaMode::Mask(0.25));
+        assert!(material.double_sided);
+        let texture = &model.textures[0];
+        assert_eq!(texture.sampler.wrap_s, 33071);
+        assert_eq!(texture.sampler.wrap_t, 10497);
+        assert_eq!(model.images[texture.image.unwrap()], Image::Uri("checker.png".to_owned()));
+
+        assert_eq!(model.nodes[0].children, vec![1, 2]);
+        let pivot = &model.nodes[2];
+        assert_eq!(pivot.translation, Vec3::wrap(5.0, 0.0, 0.0));
+        assert_eq!(pivot.scale, Vec3::wrap(2.0, 2.0, 2.0));
+
+        let mut scene = Scene::default();
+        let ids = model.instantiate(&mut scene, None);
+        assert!(ids.iter().all(Option::is_some));
+        assert_eq!(scene.roots(), &[ids[0].unwrap()]);
+        assert_eq!(scene.node(ids[3].unwrap()).unwrap().parent(), ids[2]);
+        scene.update();
+        let leaf = scene.world_matrix(ids[3].unwrap()).unwrap();
+        let p = leaf.transform_point(&Vec3::wrap(1.0, 0.0, 0.0));
+        assert!(close(p.x, 5.0) && close(p.y, 2.0) && close(p.z, 0.0));
+    }
+
+    #[test]
+    fn glb_matches_gltf() {
+        let gltf = GltfModel::parse(QUAD, files).unwrap();
+        let glb = GltfModel::parse_glb(QUAD_GLB, |uri| Err(format!("unexpected '{}'", uri))).unwrap();
+        assert_eq!(glb.meshes, gltf.meshes);
+        assert_eq!(glb.nodes, gltf.nodes);
+        assert_eq!(glb.materials, gltf.materials);
+        assert_eq!(glb.animations, gltf.animations);
+        match &glb.images[0] {
+            Image::Embedded { mime_type, data } => {
+                assert_eq!(mime_type, "image/png");
+                assert!(data.starts_with(b"\x89PNG"));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn animation_channels() {
+        let model = GltfModel::parse(QUAD, files).unwrap();
+        let animation = &model.animations[0];
+        assert_eq!(animation.name, "spin");
+        assert_eq!(animation.duration(), 1.0);
+        let [rotation, translation, scale] = [0, 1, 2].map(|i| &animation.channels[i]);
+        assert_eq!((rotation.property, rotation.interpolation), (Property::Rotation, Interpolation::Linear));
+
+        let q = rotation.sample(0.5);
+        let half = (std::f32::consts::PI / 8.0).sin();
+        assert!(close(q[1], half) && close(q[3], (std::f32::consts::PI / 8.0).cos()));
+        assert_eq!(translation.sample(0.99), vec![0.0, 0.0, 0.0]);
+        assert_eq!(translation.sample(1.0), vec![0.0, 3.0, 0.0]);
+        assert!(scale.sample(0.5).iter().all(|s| close(*s, 1.5)));
+        assert_eq!(scale.sample(-1.0), vec![1.0, 1.0, 1.0]);
+
+        let mut scene = Scene::default();
+        let ids = model.instantiate(&mut scene, None);
+        animation.apply(1.0, &mut scene, &ids);
+        let quad = scene.node(ids[1].unwrap()).unwrap();
+        assert_eq!(quad.translation(), Vec3::wrap(0.0, 3.0, 0.0));
+        assert_eq!(scene.node(ids[3].unwrap()).unwrap().scale(), Vec3::wrap(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn errors_name_the_failing_object() {
+        let missing = GltfModel::parse(QUAD, |uri| Err(format!("no file '{}'", uri))).unwrap_err();
+        assert_eq!(missing, "buffers[0]: no file 'quad.bin'");
+        let version = GltfModel::parse(r#"{"asset":{"version":"1.0"}}"#, files).unwrap_err();
+        assert_eq!(version, "unsupported glTF version '1.0'");
+        let range = GltfModel::parse(&QUAD.replace("\"indices\": 3", "\"indices\": 0"), files).unwrap_err();
+        assert_eq!(range, "meshes[0].primitives[0]: accessors[0]: indices must be unsigned scalars");
+        assert!(GltfModel::parse_glb(b"glTF", files).is_err());
+        assert!(GltfModel::parse_glb(&QUAD_GLB[..QUAD_GLB.len() - 8], files).is_err());
+        assert!(Json::parse("{\"a\": [1, 2,]}").unwrap_err().starts_with("JSON: unexpected character"));
+        let nodes = |nodes: &str| {
+            GltfModel::parse(&format!(r#"{{"asset":{{"version":"2.0"}},"nodes":{}}}"#, nodes), files).unwrap_err()
+        };
+        assert_eq!(nodes(r#"[{"children":[0]}]"#), "nodes[0]: part of a cycle");
+        assert_eq!(nodes(r#"[{"children":[1]},{"children":[0]}]"#), "nodes[0]: part of a cycle");
+        assert_eq!(nodes(r#"[{"children":[1]},{},{"children":[3]},{"children":[2]}]"#), "nodes[2]: part of a cycle");
+        assert_eq!(nodes(r#"[{"children":[2]},{"children":[2]},{}]"#), "nodes[1]: invalid child Number(2.0)");
+    }
+}
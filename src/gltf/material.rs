@@ -0,0 +1,107 @@
+use crate::glm::Color;
+use super::Json;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AlphaMode {
+    Opaque,
+    /// Fragments with alpha below the cutoff are discarded.
+    Mask(f32),
+    Blend,
+}
+
+/// A texture slot of a material, `tex_coord` picks the UV set.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextureRef {
+    pub texture: usize,
+    pub tex_coord: u32,
+}
+
+/// glTF metallic-roughness material. Factors multiply their texture when
+/// one is set, colors are linear.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PbrMaterial {
+    pub name: String,
+    pub base_color: Color,
+    pub base_color_texture: Option<TextureRef>,
+    pub metallic: f32,
+    pub roughness: f32,
+    /// Roughness in green, metalness in blue.
+    pub metallic_roughness_texture: Option<TextureRef>,
+    pub normal_texture: Option<TextureRef>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<TextureRef>,
+    pub occlusion_strength: f32,
+    pub emissive: Color,
+    pub emissive_texture: Option<TextureRef>,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+}
+
+impl Default for PbrMaterial {
+    /// The material glTF prescribes for primitives without one.
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color: Color::wrap(1.0, 1.0, 1.0, 1.0),
+            base_color_texture: None,
+            metallic: 1.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive: Color::rgb(0.0, 0.0, 0.0),
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+impl PbrMaterial {
+    pub(super) fn parse(json: &Json, textures: usize) -> Result<Self, String> {
+        let slot = |json: Option<&Json>| -> Result<Option<TextureRef>, String> {
+            let json = match json {
+                Some(json) => json,
+                None => return Ok(None),
+            };
+            match json.index("index") {
+                Some(texture) if texture < textures => Ok(Some(TextureRef {
+                    texture,
+                    tex_coord: json.index("texCoord").unwrap_or(0) as u32,
+                })),
+                other => Err(format!("texture {:?} does not exist", other)),
+            }
+        };
+        let mut material = PbrMaterial {
+            name: json.str("name").unwrap_or_default().to_owned(),
+            ..PbrMaterial::default()
+        };
+        if let Some(pbr) = json.get("pbrMetallicRoughness") {
+            if let Some([r, g, b, a]) = pbr.floats("baseColorFactor") {
+                material.base_color = Color::wrap(r, g, b, a);
+            }
+            material.base_color_texture = slot(pbr.get("baseColorTexture"))?;
+            material.metallic = pbr.f32("metallicFactor").unwrap_or(1.0);
+            material.roughness = pbr.f32("roughnessFactor").unwrap_or(1.0);
+            material.metallic_roughness_texture = slot(pbr.get("metallicRoughnessTexture"))?;
+        }
+        material.normal_texture = slot(json.get("normalTexture"))?;
+        material.normal_scale = json.get("normalTexture").and_then(|t| t.f32("scale")).unwrap_or(1.0);
+        material.occlusion_texture = slot(json.get("occlusionTexture"))?;
+        material.occlusion_strength = json.get("occlusionTexture").and_then(|t| t.f32("strength")).unwrap_or(1.0);
+        if let Some([r, g, b]) = json.floats("emissiveFactor") {
+            material.emissive = Color::rgb(r, g, b);
+        }
+        material.emissive_texture = slot(json.get("emissiveTexture"))?;
+        material.alpha_mode = match json.str("alphaMode").unwrap_or("OPAQUE") {
+            "OPAQUE" => AlphaMode::Opaque,
+            "MASK" => AlphaMode::Mask(json.f32("alphaCutoff").unwrap_or(0.5)),
+            "BLEND" => AlphaMode::Blend,
+            other => return Err(format!("unknown alpha mode '{}'", other)),
+        };
+        material.double_sided = json.get("doubleSided").and_then(Json::as_bool).unwrap_or(false);
+        Ok(material)
+    }
+}
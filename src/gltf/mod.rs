@@ -0,0 +1,13 @@
+mod accessor;
+mod animation;
+mod base64;
+mod document;
+mod json;
+mod material;
+mod texture;
+
+pub use animation::{Animation, Channel, Interpolation, Property};
+pub use document::{GltfMesh, GltfModel, GltfNode, GltfPrimitive};
+pub use json::Json;
+pub use material::{AlphaMode, PbrMaterial, TextureRef};
+pub use texture::{GltfTexture, Image, Sampler};
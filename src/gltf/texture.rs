@@ -0,0 +1,49 @@
+use web_sys::WebGl2RenderingContext as WebGl;
+use super::Json;
+
+/// Where the pixels of a glTF image come from. Embedded images still hold
+/// their encoded PNG or JPEG bytes.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Image {
+    /// Relative to the glTF file, e.g. for `utils::load_texture`.
+    Uri(String),
+    Embedded { mime_type: String, data: Vec<u8> },
+}
+
+/// Sampler state with GL enum values, filters are left to the
+/// implementation when unset.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Sampler {
+    pub mag_filter: Option<u32>,
+    pub min_filter: Option<u32>,
+    pub wrap_s: u32,
+    pub wrap_t: u32,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self {
+            mag_filter: None,
+            min_filter: None,
+            wrap_s: WebGl::REPEAT,
+            wrap_t: WebGl::REPEAT,
+        }
+    }
+}
+
+impl Sampler {
+    pub(super) fn parse(json: &Json) -> Self {
+        Self {
+            mag_filter: json.index("magFilter").map(|f| f as u32),
+            min_filter: json.index("minFilter").map(|f| f as u32),
+            wrap_s: json.index("wrapS").map_or(WebGl::REPEAT, |w| w as u32),
+            wrap_t: json.index("wrapT").map_or(WebGl::REPEAT, |w| w as u32),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GltfTexture {
+    pub image: Option<usize>,
+    pub sampler: Sampler,
+}
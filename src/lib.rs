@@ -1,6 +1,11 @@
-mod engine;
-mod glm;
-mod obj;
+pub mod backend;
+pub mod camera;
+pub mod engine;
+pub mod glm;
+pub mod gltf;
+pub mod obj;
+pub mod scene;
+pub mod shader;
 mod utils;
 
 
@@ -11,10 +16,11 @@ use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{Element, WebGl2RenderingContext as WebGl};
+use crate::backend::WebGlBackend;
 use crate::engine::Engine;
 
 fn request_animation_frame(
-    closure: &Closure<dyn FnMut()>
+    closure: &Closure<dyn FnMut(f64)>
 ) -> Result<i32, JsValue> {
     web_sys::window().unwrap()
         .request_animation_frame(
@@ -38,7 +44,7 @@ pub fn run() -> Result<(), JsValue> {
     //
     let pressed = Rc::new(Cell::new(false));
     let engine = Rc::new(RefCell::new(
-        Engine::create(gl),
+        Engine::create(Rc::new(WebGlBackend::wrap(gl))),
     ));
     //
     engine.borrow_mut().setup();
@@ -106,6 +112,22 @@ pub fn run() -> Result<(), JsValue> {
         )?;
         closure.forget();
     }
+    // press `c` to dump the GL calls of one frame to the console
+    {
+        let engine = engine.clone();
+        let closure = Closure::wrap(Box::new(
+            move |event: web_sys::KeyboardEvent| {
+                if event.key() == "c" {
+                    let trace = engine.borrow_mut().capture_frame();
+                    web_sys::console::log_1(&trace.dump().into());
+                }
+            }
+        ) as Box<dyn FnMut(_)>);
+        window.add_event_listener_with_callback(
+            "keydown", closure.as_ref().unchecked_ref(),
+        )?;
+        closure.forget();
+    }
 
     //
     {
@@ -113,13 +135,14 @@ pub fn run() -> Result<(), JsValue> {
         let callback = Rc::new(RefCell::new(None));
         let callback_ = callback.clone();
         *callback.borrow_mut() = Some(Closure::wrap(Box::new(
-            move || {
+            move |stamp: f64| {
+                engine.borrow_mut().set_time(stamp);
                 engine.borrow_mut().update();
                 request_animation_frame(
                     callback_.borrow().as_ref().unwrap()
                 ).unwrap();
             }
-        ) as Box<dyn FnMut()>));
+        ) as Box<dyn FnMut(f64)>));
         request_animation_frame(
             callback.borrow().as_ref().unwrap()
         ).unwrap();
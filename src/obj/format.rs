@@ -0,0 +1,63 @@
+use web_sys::WebGl2RenderingContext as WebGl;
+
+/// Component type and count of one vertex attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VertexFormat {
+    Float32,
+    Float32x2,
+    Float32x3,
+    Float32x4,
+    Uint8x2,
+    Uint8x4,
+    Int8x2,
+    Int8x4,
+    Uint16x2,
+    Uint16x4,
+    Int16x2,
+    Int16x4,
+    Uint32,
+    Uint32x2,
+    Uint32x3,
+    Uint32x4,
+}
+
+impl VertexFormat {
+    /// GL component type, as passed to `vertex_attrib_pointer`.
+    pub fn gl_type(&self) -> u32 {
+        match self {
+            VertexFormat::Float32 | VertexFormat::Float32x2
+            | VertexFormat::Float32x3 | VertexFormat::Float32x4 => WebGl::FLOAT,
+            VertexFormat::Uint8x2 | VertexFormat::Uint8x4 => WebGl::UNSIGNED_BYTE,
+            VertexFormat::Int8x2 | VertexFormat::Int8x4 => WebGl::BYTE,
+            VertexFormat::Uint16x2 | VertexFormat::Uint16x4 => WebGl::UNSIGNED_SHORT,
+            VertexFormat::Int16x2 | VertexFormat::Int16x4 => WebGl::SHORT,
+            VertexFormat::Uint32 | VertexFormat::Uint32x2
+            | VertexFormat::Uint32x3 | VertexFormat::Uint32x4 => WebGl::UNSIGNED_INT,
+        }
+    }
+
+    pub fn components(&self) -> i32 {
+        match self {
+            VertexFormat::Float32 | VertexFormat::Uint32 => 1,
+            VertexFormat::Float32x2 | VertexFormat::Uint8x2 | VertexFormat::Int8x2
+            | VertexFormat::Uint16x2 | VertexFormat::Int16x2 | VertexFormat::Uint32x2 => 2,
+            VertexFormat::Float32x3 | VertexFormat::Uint32x3 => 3,
+            VertexFormat::Float32x4 | VertexFormat::Uint8x4 | VertexFormat::Int8x4
+            | VertexFormat::Uint16x4 | VertexFormat::Int16x4 | VertexFormat::Uint32x4 => 4,
+        }
+    }
+
+    /// Size of one value in bytes.
+    pub fn size(&self) -> usize {
+        let component = match self.gl_type() {
+            WebGl::UNSIGNED_BYTE | WebGl::BYTE => 1,
+            WebGl::UNSIGNED_SHORT | WebGl::SHORT => 2,
+            _ => 4,
+        };
+        component * self.components() as usize
+    }
+
+    pub fn is_float(&self) -> bool {
+        self.gl_type() == WebGl::FLOAT
+    }
+}
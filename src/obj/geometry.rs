@@ -0,0 +1,81 @@
+use std::rc::Rc;
+use crate::backend::{self, Backend};
+use crate::glm::{Vec2, Vec3};
+use super::{Indices, Mesh, Primitive, VertexAttribute, VertexFormat, VertexLayout};
+
+/// Triangle list with per-vertex positions, unit normals and UVs, as
+/// produced by the generators in `shapes`. Triangles wind counter-clockwise
+/// seen from the side their normals face, `v = 0` is the top of a texture.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Geometry {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Vec2>,
+    pub indices: Vec<u32>,
+}
+
+impl Geometry {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Appends a vertex and returns its index.
+    pub fn vertex(&mut self, position: Vec3, normal: Vec3, uv: Vec2) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        self.positions.len() as u32 - 1
+    }
+
+    pub fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend([a, b, c]);
+    }
+
+    /// Two triangles for the quad `a b c d` given counter-clockwise.
+    pub fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+}
+
+impl Geometry {
+    /// Uploads one stream per attribute, normals and UVs only when given
+    /// a location. Indices are 16-bit when the vertex count allows.
+    pub fn upload(
+        &self,
+        gl: &Rc<dyn Backend>,
+        position: u32,
+        normal: Option<u32>,
+        uv: Option<u32>,
+    ) -> Result<Mesh, String> {
+        let mut attributes = vec![VertexAttribute::wrap(position, VertexFormat::Float32x3)];
+        let mut streams = vec![backend::f32_bytes(
+            &self.positions.iter().flat_map(|p| [p.x, p.y, p.z]).collect::<Vec<f32>>(),
+        )];
+        if let Some(location) = normal {
+            attributes.push(VertexAttribute::wrap(location, VertexFormat::Float32x3));
+            streams.push(backend::f32_bytes(
+                &self.normals.iter().flat_map(|n| [n.x, n.y, n.z]).collect::<Vec<f32>>(),
+            ));
+        }
+        if let Some(location) = uv {
+            attributes.push(VertexAttribute::wrap(location, VertexFormat::Float32x2));
+            streams.push(backend::f32_bytes(
+                &self.uvs.iter().flat_map(|t| [t.x, t.y]).collect::<Vec<f32>>(),
+            ));
+        }
+        let streams: Vec<&[u8]> = streams.iter().map(Vec::as_slice).collect();
+        let indices = Indices::fit(self.indices.clone(), self.vertex_count());
+        Mesh::create(
+            gl,
+            &VertexLayout::separate(attributes),
+            &streams,
+            Some(&indices),
+            Primitive::Triangles,
+        )
+    }
+}
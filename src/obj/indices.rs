@@ -0,0 +1,67 @@
+use web_sys::WebGl2RenderingContext as WebGl;
+
+/// Index data of a mesh, 16-bit where the vertex count allows.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// Picks the narrowest type that can address `vertices`. `0xFFFF` is
+    /// never used as a 16-bit index, WebGL2 always treats it as a
+    /// primitive restart.
+    pub fn fit(indices: Vec<u32>, vertices: usize) -> Self {
+        if vertices <= u16::MAX as usize {
+            Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    pub fn gl_type(&self) -> u32 {
+        match self {
+            Indices::U16(_) => WebGl::UNSIGNED_SHORT,
+            Indices::U32(_) => WebGl::UNSIGNED_INT,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(v) => v.len(),
+            Indices::U32(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        match self {
+            Indices::U16(v) => v.iter().max().map(|i| *i as u32),
+            Indices::U32(v) => v.iter().max().copied(),
+        }
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        match self {
+            Indices::U16(v) => v.iter().flat_map(|i| i.to_le_bytes()).collect(),
+            Indices::U32(v) => v.iter().flat_map(|i| i.to_le_bytes()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_keeps_the_restart_index_free() {
+        let last = u16::MAX as u32 - 1;
+        assert_eq!(Indices::fit(vec![0, last], last as usize + 1), Indices::U16(vec![0, u16::MAX - 1]));
+        let indices = Indices::fit(vec![0, last + 1], last as usize + 2);
+        assert_eq!(indices, Indices::U32(vec![0, u16::MAX as u32]));
+        assert_eq!(indices.gl_type(), WebGl::UNSIGNED_INT);
+    }
+}
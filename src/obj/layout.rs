@@ -0,0 +1,92 @@
+use super::VertexFormat;
+
+/// One attribute of a vertex, `normalized` maps integer formats to
+/// `[0, 1]` or `[-1, 1]` instead of converting them to float as-is.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VertexAttribute {
+    pub location: u32,
+    pub format: VertexFormat,
+    pub normalized: bool,
+}
+
+impl VertexAttribute {
+    pub fn wrap(location: u32, format: VertexFormat) -> Self {
+        Self { location, format, normalized: false }
+    }
+
+    pub fn normalized(location: u32, format: VertexFormat) -> Self {
+        Self { location, format, normalized: true }
+    }
+}
+
+/// How vertex data is laid out: all attributes packed in one stream in
+/// declaration order, or one tightly packed stream per attribute.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VertexLayout {
+    pub attributes: Vec<VertexAttribute>,
+    pub interleaved: bool,
+}
+
+impl VertexLayout {
+    pub fn interleaved(attributes: Vec<VertexAttribute>) -> Self {
+        Self { attributes, interleaved: true }
+    }
+
+    pub fn separate(attributes: Vec<VertexAttribute>) -> Self {
+        Self { attributes, interleaved: false }
+    }
+}
+
+impl VertexLayout {
+    /// Number of byte streams `Mesh::create` expects.
+    pub fn streams(&self) -> usize {
+        if self.interleaved { 1 } else { self.attributes.len() }
+    }
+
+    /// Bytes per vertex in `stream`.
+    pub fn stride(&self, stream: usize) -> usize {
+        if self.interleaved {
+            self.attributes.iter().map(|a| a.format.size()).sum()
+        } else {
+            self.attributes[stream].format.size()
+        }
+    }
+
+    /// Stream and byte offset of the attribute at `index`.
+    pub fn placement(&self, index: usize) -> (usize, usize) {
+        if self.interleaved {
+            (0, self.attributes[..index].iter().map(|a| a.format.size()).sum())
+        } else {
+            (index, 0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes() -> Vec<VertexAttribute> {
+        vec![
+            VertexAttribute::wrap(0, VertexFormat::Float32x3),
+            VertexAttribute::normalized(1, VertexFormat::Uint8x4),
+            VertexAttribute::wrap(2, VertexFormat::Float32x2),
+        ]
+    }
+
+    #[test]
+    fn interleaved_attributes_share_one_stream() {
+        let layout = VertexLayout::interleaved(attributes());
+        assert_eq!(layout.streams(), 1);
+        assert_eq!(layout.stride(0), 12 + 4 + 8);
+        assert_eq!((0..3).map(|i| layout.placement(i)).collect::<Vec<_>>(), [(0, 0), (0, 12), (0, 16)]);
+    }
+
+    #[test]
+    fn separate_attributes_get_a_stream_each() {
+        let layout = VertexLayout::separate(attributes());
+        assert_eq!(layout.streams(), 3);
+        assert_eq!((0..3).map(|i| layout.stride(i)).collect::<Vec<_>>(), [12, 4, 8]);
+        assert_eq!((0..3).map(|i| layout.placement(i)).collect::<Vec<_>>(), [(0, 0), (1, 0), (2, 0)]);
+    }
+}
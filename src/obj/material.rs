@@ -0,0 +1,146 @@
+use crate::glm::Color;
+use super::parse_error::{floats, statements};
+use super::ParseError;
+
+/// A Wavefront MTL material. Colors are as written in the file, texture
+/// maps are paths relative to the MTL file with their options dropped.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Material {
+    pub name: String,
+    pub ambient: Color,
+    pub diffuse: Color,
+    pub specular: Color,
+    pub emissive: Color,
+    pub shininess: f32,
+    /// `d`, or `1 - Tr`.
+    pub opacity: f32,
+    pub optical_density: f32,
+    pub illumination: u32,
+    pub ambient_map: Option<String>,
+    pub diffuse_map: Option<String>,
+    pub specular_map: Option<String>,
+    pub emissive_map: Option<String>,
+    pub alpha_map: Option<String>,
+    pub normal_map: Option<String>,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            ambient: Color::rgb(0.0, 0.0, 0.0),
+            diffuse: Color::rgb(0.8, 0.8, 0.8),
+            specular: Color::rgb(0.0, 0.0, 0.0),
+            emissive: Color::rgb(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            opacity: 1.0,
+            optical_density: 1.0,
+            illumination: 2,
+            ambient_map: None,
+            diffuse_map: None,
+            specular_map: None,
+            emissive_map: None,
+            alpha_map: None,
+            normal_map: None,
+        }
+    }
+}
+
+impl Material {
+    /// Every `newmtl` in an MTL file, in file order.
+    pub fn parse_library(source: &str) -> Result<Vec<Material>, ParseError> {
+        let mut materials: Vec<Material> = Vec::new();
+        for (line, text) in statements(source) {
+            let mut words = text.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            let args: Vec<&str> = words.collect();
+            if keyword == "newmtl" {
+                let name = args.join(" ");
+                if name.is_empty() {
+                    return Err(ParseError::wrap(line, "'newmtl' needs a name"));
+                }
+                materials.push(Material { name, ..Material::default() });
+                continue;
+            }
+            let material = materials.last_mut().ok_or_else(|| {
+                ParseError::wrap(line, format!("'{}' before any 'newmtl'", keyword))
+            })?;
+            let color = |args: &[&str]| -> Result<Color, ParseError> {
+                if args.first() == Some(&"spectral") || args.first() == Some(&"xyz") {
+                    return Err(ParseError::wrap(line, format!("'{}' only supports RGB colors", keyword)));
+                }
+                // A single value sets all three channels.
+                let [r] = floats(line, keyword, args, [None])?;
+                let [_, g, b] = floats(line, keyword, args, [None, Some(r), Some(r)])?;
+                Ok(Color::rgb(r, g, b))
+            };
+            let map = |args: &[&str]| -> Result<String, ParseError> {
+                args.last()
+                    .map(|path| (*path).to_owned())
+                    .ok_or_else(|| ParseError::wrap(line, format!("'{}' needs a file name", keyword)))
+            };
+            match keyword {
+                "Ka" => material.ambient = color(&args)?,
+                "Kd" => material.diffuse = color(&args)?,
+                "Ks" => material.specular = color(&args)?,
+                "Ke" => material.emissive = color(&args)?,
+                "Ns" => material.shininess = floats(line, keyword, &args, [None])?[0],
+                "Ni" => material.optical_density = floats(line, keyword, &args, [None])?[0],
+                "d" => material.opacity = floats(line, keyword, &args, [None])?[0],
+                "Tr" => material.opacity = 1.0 - floats(line, keyword, &args, [None])?[0],
+                "illum" => {
+                    material.illumination = args.first().and_then(|a| a.parse().ok()).ok_or_else(|| {
+                        ParseError::wrap(line, "'illum' expects a model number")
+                    })?
+                }
+                "map_Ka" => material.ambient_map = Some(map(&args)?),
+                "map_Kd" => material.diffuse_map = Some(map(&args)?),
+                "map_Ks" => material.specular_map = Some(map(&args)?),
+                "map_Ke" => material.emissive_map = Some(map(&args)?),
+                "map_d" => material.alpha_map = Some(map(&args)?),
+                "map_Bump" | "map_bump" | "bump" | "norm" => material.normal_map = Some(map(&args)?),
+                _ => {}
+            }
+        }
+        Ok(materials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_materials_in_order() {
+        let materials = Material::parse_library(
+            "# exported\nnewmtl red paint\nKa 0.1\nKd 1 0 0\nKs 0.5 0.5 0.5\nNs 32\nTr 0.25\nillum 1\n\
+             map_Kd -s 2 2 1 textures/red.png\nbump -bm 0.5 normal.png\n\
+             newmtl glass\nd 0.1\nNi 1.5\n",
+        ).unwrap();
+        assert_eq!(materials.len(), 2);
+        let red = &materials[0];
+        assert_eq!(red.name, "red paint");
+        assert_eq!(red.ambient, Color::rgb(0.1, 0.1, 0.1));
+        assert_eq!(red.diffuse, Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!((red.shininess, red.opacity, red.illumination), (32.0, 0.75, 1));
+        assert_eq!(red.diffuse_map.as_deref(), Some("textures/red.png"));
+        assert_eq!(red.normal_map.as_deref(), Some("normal.png"));
+        assert_eq!(red.specular_map, None);
+        let glass = &materials[1];
+        assert_eq!((glass.opacity, glass.optical_density), (0.1, 1.5));
+        assert_eq!(glass.diffuse, Material::default().diffuse);
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let e = Material::parse_library("\nKd 1 1 1\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: 'Kd' before any 'newmtl'");
+        let e = Material::parse_library("newmtl a\nKd spectral file.rfl\n").unwrap_err();
+        assert_eq!((e.line, e.message.as_str()), (2, "'Kd' only supports RGB colors"));
+        let e = Material::parse_library("newmtl a\nNs \\\n\nillum two\n").unwrap_err();
+        assert_eq!((e.line, e.message.as_str()), (2, "'Ns' needs at least 1 values"));
+        let e = Material::parse_library("newmtl a\nillum two\n").unwrap_err();
+        assert_eq!((e.line, e.message.as_str()), (2, "'illum' expects a model number"));
+        assert_eq!(Material::parse_library("newmtl\n").unwrap_err().line, 1);
+    }
+}
@@ -0,0 +1,256 @@
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::{Backend, Buffer, VertexArray};
+use super::{Indices, Primitive, VertexLayout};
+
+/// Vertex streams and optional indices uploaded into a vertex array.
+pub struct Mesh {
+    gl: Rc<dyn Backend>,
+    vao: Option<VertexArray>,
+    buffers: Vec<Option<Buffer>>,
+    index_buffer: Option<Buffer>,
+    index_type: Option<u32>,
+    count: i32,
+    primitive: Primitive,
+}
+
+impl Drop for Mesh {
+    fn drop(&mut self) {
+        self.gl.delete_vertex_array(self.vao.as_ref());
+        for buffer in &self.buffers {
+            self.gl.delete_buffer(buffer.as_ref());
+        }
+        if self.index_buffer.is_some() {
+            self.gl.delete_buffer(self.index_buffer.as_ref());
+        }
+    }
+}
+
+impl Mesh {
+    /// Uploads one byte stream per `layout.streams()`, all holding the
+    /// same number of vertices. Without indices every vertex is drawn in
+    /// order.
+    pub fn create(
+        gl: &Rc<dyn Backend>,
+        layout: &VertexLayout,
+        streams: &[&[u8]],
+        indices: Option<&Indices>,
+        primitive: Primitive,
+    ) -> Result<Self, String> {
+        let vertices = Self::validate(layout, streams, indices)?;
+        //
+        let vao = gl.create_vertex_array();
+        gl.bind_vertex_array(vao.as_ref());
+        let buffers: Vec<Option<Buffer>> = streams.iter()
+            .map(|data| {
+                let buffer = gl.create_buffer();
+                gl.bind_buffer(WebGl::ARRAY_BUFFER, buffer.as_ref());
+                gl.buffer_data(WebGl::ARRAY_BUFFER, data, WebGl::STATIC_DRAW);
+                buffer
+            })
+            .collect();
+        for (i, attribute) in layout.attributes.iter().enumerate() {
+            let (stream, offset) = layout.placement(i);
+            gl.bind_buffer(WebGl::ARRAY_BUFFER, buffers[stream].as_ref());
+            gl.enable_vertex_attrib_array(attribute.location);
+            gl.vertex_attrib_pointer(
+                attribute.location,
+                attribute.format.components(),
+                attribute.format.gl_type(),
+                attribute.normalized,
+                layout.stride(stream) as i32,
+                offset as i32,
+            );
+        }
+        let index_buffer = indices.and_then(|indices| {
+            let buffer = gl.create_buffer();
+            gl.bind_buffer(WebGl::ELEMENT_ARRAY_BUFFER, buffer.as_ref());
+            gl.buffer_data(WebGl::ELEMENT_ARRAY_BUFFER, &indices.bytes(), WebGl::STATIC_DRAW);
+            buffer
+        });
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(WebGl::ARRAY_BUFFER, None);
+        if index_buffer.is_some() {
+            gl.bind_buffer(WebGl::ELEMENT_ARRAY_BUFFER, None);
+        }
+        //
+        Ok(Self {
+            gl: gl.clone(),
+            vao,
+            buffers,
+            index_buffer,
+            index_type: indices.map(Indices::gl_type),
+            count: indices.map_or(vertices, Indices::len) as i32,
+            primitive,
+        })
+    }
+
+    /// Vertex count shared by all streams.
+    fn validate(layout: &VertexLayout, streams: &[&[u8]], indices: Option<&Indices>) -> Result<usize, String> {
+        if layout.attributes.is_empty() {
+            return Err("Vertex layout has no attributes".to_owned());
+        }
+        for (i, a) in layout.attributes.iter().enumerate() {
+            if layout.attributes[..i].iter().any(|b| b.location == a.location) {
+                return Err(format!("Attribute location {} is used twice", a.location));
+            }
+            if a.normalized && a.format.is_float() {
+                return Err(format!("Attribute {} is a float format and can't be normalized", a.location));
+            }
+        }
+        if streams.len() != layout.streams() {
+            return Err(format!("Expected {} vertex streams, got {}", layout.streams(), streams.len()));
+        }
+        let mut vertices = None;
+        for (i, data) in streams.iter().enumerate() {
+            let stride = layout.stride(i);
+            if data.len() % stride != 0 {
+                return Err(format!("Stream {} is {} bytes, not a multiple of its {} byte stride", i, data.len(), stride));
+            }
+            let count = data.len() / stride;
+            if vertices.is_some_and(|v| v != count) {
+                return Err(format!("Stream {} has {} vertices, expected {}", i, count, vertices.unwrap()));
+            }
+            vertices = Some(count);
+        }
+        let vertices = vertices.unwrap_or(0);
+        if let Some(max) = indices.and_then(Indices::max) {
+            if max as usize >= vertices {
+                return Err(format!("Index {} is out of range for {} vertices", max, vertices));
+            }
+        }
+        Ok(vertices)
+    }
+}
+
+impl Mesh {
+    pub fn primitive(&self) -> Primitive {
+        self.primitive
+    }
+
+    /// Number of vertices drawn, the index count for indexed meshes.
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.index_type.is_some()
+    }
+
+    /// Issues the draw call, the program and its uniforms must be set.
+    pub fn draw(&self) {
+        self.gl.bind_vertex_array(self.vao.as_ref());
+        match self.index_type {
+            Some(type_) => self.gl.draw_elements(self.primitive.gl_mode(), self.count, type_, 0),
+            None => self.gl.draw_arrays(self.primitive.gl_mode(), 0, self.count),
+        }
+        self.gl.bind_vertex_array(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{Call, Headless};
+    use super::super::{VertexAttribute, VertexFormat};
+    use super::*;
+
+    fn backend() -> (Rc<Headless>, Rc<dyn Backend>) {
+        let headless = Rc::new(Headless::default());
+        (headless.clone(), headless)
+    }
+
+    fn separate() -> VertexLayout {
+        VertexLayout::separate(vec![
+            VertexAttribute::wrap(0, VertexFormat::Float32x3),
+            VertexAttribute::wrap(3, VertexFormat::Float32x2),
+        ])
+    }
+
+    fn pointers(calls: &[Call]) -> Vec<Call> {
+        calls.iter().filter(|c| matches!(c, Call::VertexAttribPointer(..))).cloned().collect()
+    }
+
+    #[test]
+    fn interleaved_attributes_point_into_one_buffer() {
+        let (headless, gl) = backend();
+        let layout = VertexLayout::interleaved(vec![
+            VertexAttribute::wrap(0, VertexFormat::Float32x3),
+            VertexAttribute::normalized(1, VertexFormat::Uint8x4),
+            VertexAttribute::wrap(2, VertexFormat::Float32x2),
+        ]);
+        let mesh = Mesh::create(&gl, &layout, &[&[0; 24 * 3]], None, Primitive::Triangles).unwrap();
+        assert_eq!(pointers(&headless.take_calls()), [
+            Call::VertexAttribPointer(0, 3, WebGl::FLOAT, false, 24, 0),
+            Call::VertexAttribPointer(1, 4, WebGl::UNSIGNED_BYTE, true, 24, 12),
+            Call::VertexAttribPointer(2, 2, WebGl::FLOAT, false, 24, 16),
+        ]);
+        assert_eq!((mesh.count(), mesh.is_indexed()), (3, false));
+        mesh.draw();
+        assert!(headless.take_calls().contains(&Call::DrawArrays(WebGl::TRIANGLES, 0, 3)));
+        drop(mesh);
+        assert!(headless.objects().is_empty());
+    }
+
+    #[test]
+    fn separate_attributes_point_into_their_own_buffer() {
+        let (headless, gl) = backend();
+        let indices = Indices::fit(vec![0, 1, 2, 0, 2, 3], 4);
+        let streams: [&[u8]; 2] = [&[0; 12 * 4], &[0; 8 * 4]];
+        let mesh = Mesh::create(&gl, &separate(), &streams, Some(&indices), Primitive::TriangleStrip).unwrap();
+        let calls = headless.take_calls();
+        assert_eq!(pointers(&calls), [
+            Call::VertexAttribPointer(0, 3, WebGl::FLOAT, false, 12, 0),
+            Call::VertexAttribPointer(3, 2, WebGl::FLOAT, false, 8, 0),
+        ]);
+        // Each pointer reads from the buffer bound just before it.
+        let bound = |location| {
+            let at = calls.iter()
+                .position(|c| matches!(c, Call::VertexAttribPointer(l, ..) if *l == location))
+                .unwrap();
+            calls[..at].iter().rev().find_map(|c| match c {
+                Call::BindBuffer(WebGl::ARRAY_BUFFER, b) => Some(*b),
+                _ => None,
+            })
+        };
+        assert_ne!(bound(0), bound(3));
+        let index_data = calls.iter().find_map(|c| match c {
+            Call::BufferData(WebGl::ELEMENT_ARRAY_BUFFER, data, _) => Some(data.len()),
+            _ => None,
+        });
+        assert_eq!(index_data, Some(6 * 2));
+        mesh.draw();
+        let draw = Call::DrawElements(WebGl::TRIANGLE_STRIP, 6, WebGl::UNSIGNED_SHORT, 0);
+        assert!(headless.take_calls().contains(&draw));
+        drop(mesh);
+        assert!(headless.objects().is_empty());
+    }
+
+    #[test]
+    fn invalid_data_is_rejected_before_upload() {
+        let (headless, gl) = backend();
+        let (positions, texcoords) = ([0u8; 12 * 4], [0u8; 8 * 4]);
+        let create = |layout: &VertexLayout, streams: &[&[u8]], indices: Option<&Indices>| {
+            Mesh::create(&gl, layout, streams, indices, Primitive::Points).err().unwrap()
+        };
+        assert_eq!(create(&separate(), &[&positions], None), "Expected 2 vertex streams, got 1");
+        assert_eq!(
+            create(&separate(), &[&positions, &texcoords[..24]], None),
+            "Stream 1 has 3 vertices, expected 4",
+        );
+        assert_eq!(
+            create(&separate(), &[&positions[..25], &texcoords], None),
+            "Stream 0 is 25 bytes, not a multiple of its 12 byte stride",
+        );
+        assert_eq!(
+            create(&separate(), &[&positions, &texcoords], Some(&Indices::U32(vec![0, 4]))),
+            "Index 4 is out of range for 4 vertices",
+        );
+        let float = VertexAttribute::normalized(0, VertexFormat::Float32);
+        let normalized = VertexLayout::interleaved(vec![float]);
+        assert_eq!(
+            create(&normalized, &[&[0; 4]], None),
+            "Attribute 0 is a float format and can't be normalized",
+        );
+        assert!(headless.calls().is_empty());
+    }
+}
@@ -1,3 +1,22 @@
+mod format;
+mod geometry;
+mod indices;
+mod layout;
+mod material;
+mod mesh;
+mod parse_error;
+mod primitive;
 mod quad;
+mod wavefront;
+pub mod shapes;
 
+pub use format::VertexFormat;
+pub use geometry::Geometry;
+pub use indices::Indices;
+pub use layout::{VertexAttribute, VertexLayout};
+pub use material::Material;
+pub use mesh::Mesh;
+pub use parse_error::ParseError;
+pub use primitive::Primitive;
 pub use quad::Quad;
+pub use wavefront::{ObjMesh, ObjModel};
@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// A file that could not be parsed, `line` is 1-based.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn wrap(line: usize, message: impl Into<String>) -> Self {
+        Self { line, message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(e: ParseError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Logical lines of a Wavefront file with comments stripped and `\`
+/// continuations joined, numbered by the line they start on.
+pub(super) fn statements(source: &str) -> Vec<(usize, String)> {
+    let mut out: Vec<(usize, String)> = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (i, raw) in source.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        let (number, mut text) = pending.take().unwrap_or((i + 1, String::new()));
+        match line.trim_end().strip_suffix('\\') {
+            Some(head) => {
+                text.push_str(head);
+                text.push(' ');
+                pending = Some((number, text));
+            }
+            None => {
+                text.push_str(line);
+                if !text.trim().is_empty() {
+                    out.push((number, text.trim().to_owned()));
+                }
+            }
+        }
+    }
+    if let Some((number, text)) = pending {
+        if !text.trim().is_empty() {
+            out.push((number, text.trim().to_owned()));
+        }
+    }
+    out
+}
+
+/// Parses `N` floats from `args`, missing trailing values take
+/// `defaults`.
+pub(super) fn floats<const N: usize>(
+    line: usize,
+    keyword: &str,
+    args: &[&str],
+    defaults: [Option<f32>; N],
+) -> Result<[f32; N], ParseError> {
+    let mut out = [0.0; N];
+    for (i, default) in defaults.iter().enumerate() {
+        out[i] = match (args.get(i), default) {
+            (Some(arg), _) => arg.parse().map_err(|_| {
+                ParseError::wrap(line, format!("'{}' expects numbers, got '{}'", keyword, arg))
+            })?,
+            (None, Some(default)) => *default,
+            (None, None) => {
+                return Err(ParseError::wrap(line, format!("'{}' needs at least {} values", keyword, i + 1)));
+            }
+        };
+    }
+    Ok(out)
+}
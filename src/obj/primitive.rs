@@ -0,0 +1,26 @@
+use web_sys::WebGl2RenderingContext as WebGl;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Primitive {
+    Points,
+    Lines,
+    LineLoop,
+    LineStrip,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Primitive {
+    pub fn gl_mode(&self) -> u32 {
+        match self {
+            Primitive::Points => WebGl::POINTS,
+            Primitive::Lines => WebGl::LINES,
+            Primitive::LineLoop => WebGl::LINE_LOOP,
+            Primitive::LineStrip => WebGl::LINE_STRIP,
+            Primitive::Triangles => WebGl::TRIANGLES,
+            Primitive::TriangleStrip => WebGl::TRIANGLE_STRIP,
+            Primitive::TriangleFan => WebGl::TRIANGLE_FAN,
+        }
+    }
+}
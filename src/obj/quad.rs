@@ -1,135 +1,199 @@
-use js_sys::Float32Array;
-use wasm_bindgen::JsValue;
-use web_sys::{WebGl2RenderingContext as WebGl, WebGlBuffer};
-use web_sys::WebGlVertexArrayObject;
-use web_sys::WebGlProgram;
-use web_sys::WebGlTexture;
-use web_sys::WebGlUniformLocation;
-use crate::engine::Context;
-use crate::utils;
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::{self, Texture};
+use crate::engine::{Context, Renderable};
+use crate::shader::{ShaderProgram, ShaderVariants, UniformError};
+use super::{Indices, Mesh, Primitive, VertexAttribute, VertexFormat, VertexLayout};
 
-const FLOAT_SIZE: usize = std::mem::size_of::<f32>();
+const QUAD_VS: &str = include_str!("../shader/glsl/quad.vert");
+const QUAD_FS: &str = include_str!("../shader/glsl/quad.frag");
 
 pub struct Quad {
-    gl: WebGl,
-    pro: Option<WebGlProgram>,
-    buf: Option<WebGlBuffer>,
-    vao: Option<WebGlVertexArrayObject>,
-    upm: Option<WebGlUniformLocation>,
-    uvm: Option<WebGlUniformLocation>,
-}
-
-impl Drop for Quad {
-    fn drop(&mut self) {
-        self.gl.delete_program(self.pro.as_ref());
-        self.gl.delete_vertex_array(self.vao.as_ref());
-        self.gl.delete_buffer(self.buf.as_ref());
-    }
+    shader: Rc<ShaderProgram>,
+    mesh: Mesh,
 }
 
 impl Quad {
-    pub fn create(context: &dyn Context) -> Result<Self, JsValue> {
+    pub fn create(context: &dyn Context) -> Result<Self, String> {
         let gl = context.gl().clone();
         //
-        let vao = gl.create_vertex_array();
-        gl.bind_vertex_array(vao.as_ref());
+        let variants = ShaderVariants::named("quad.vert", QUAD_VS, "quad.frag", QUAD_FS);
+        let shader = variants.program(context.programs(), context.shaders(), &["TEXTURED"])?;
+        shader.bind();
+        shader.set("uSampler", 0)?;
+        gl.active_texture(WebGl::TEXTURE0);
+        let position = shader.attribute("position").ok_or("Missing attribute 'position'")?.location as u32;
+        let texcoord = shader.attribute("texcoord").ok_or("Missing attribute 'texcoord'")?.location as u32;
         //
         let vertices: Vec<f32> = vec![
             1.0, 1.0, 1.0, 0.0,
             1.0, -1.0, 1.0, 1.0,
             -1.0, -1.0, 0.0, 1.0,
-            -1.0, -1.0, 0.0, 1.0,
             -1.0, 1.0, 0.0, 0.0,
-            1.0, 1.0, 1.0, 0.0,
         ];
-        let buffer = gl.create_buffer();
-        gl.bind_buffer(WebGl::ARRAY_BUFFER, buffer.as_ref());
-        let array_buffer = unsafe { Float32Array::view(vertices.as_slice()) };
-        gl.buffer_data_with_array_buffer_view(
-            WebGl::ARRAY_BUFFER,
-            &array_buffer,
-            WebGl::STATIC_DRAW,
-        );
-        //
-        let v_shader = Some(utils::create_shader(
-            gl.as_ref(),
-            WebGl::VERTEX_SHADER,
-            r###"#version 300 es
-            precision highp float;
-            layout(location = 0) in vec2 position;
-            layout(location = 1) in vec2 texcoord;
-            uniform mat4 uvm;
-            uniform mat4 upm;
-            out vec2 vTexCoord;
-            void main() {
-                gl_Position = upm * uvm * vec4(position, 0.0, 1.0);
-                vTexCoord = texcoord;
-            }
-            "###,
-        )?);
-        let f_shader = Some(utils::create_shader(
-            gl.as_ref(),
-            WebGl::FRAGMENT_SHADER,
-            r###"#version 300 es
-            precision highp float;
-            in vec2 vTexCoord;
-            uniform sampler2D uSampler;
-            out vec4 outColor;
-            void main() {
-                outColor = texture(uSampler, vTexCoord);
-            }
-            "###,
-        )?);
-        let pro = Some(utils::create_program(
-            gl.as_ref(),
-            v_shader.as_ref().unwrap(),
-            f_shader.as_ref().unwrap(),
-        )?);
+        let layout = VertexLayout::interleaved(vec![
+            VertexAttribute::wrap(position, VertexFormat::Float32x2),
+            VertexAttribute::wrap(texcoord, VertexFormat::Float32x2),
+        ]);
+        let mesh = Mesh::create(
+            &gl,
+            &layout,
+            &[&backend::f32_bytes(vertices.as_slice())],
+            Some(&Indices::U16(vec![0, 1, 2, 2, 3, 0])),
+            Primitive::Triangles,
+        )?;
         //
-        gl.use_program(pro.as_ref());
-        gl.bind_attrib_location(pro.as_ref().unwrap(), 0, "position");
-        gl.bind_attrib_location(pro.as_ref().unwrap(), 1, "texcoord");
-        let upm = gl.get_uniform_location(pro.as_ref().unwrap(), "upm");
-        let uvm = gl.get_uniform_location(pro.as_ref().unwrap(), "uvm");
-        let tex = gl.get_uniform_location(pro.as_ref().unwrap(), "uSampler");
+        Ok(Self { shader, mesh })
+    }
+}
+
+impl Renderable for Quad {
+    fn draw(&self, context: &dyn Context, texture: Option<&Texture>) -> Result<(), UniformError> {
+        let gl = context.gl().clone();
         //
-        gl.enable_vertex_attrib_array(0);
-        gl.vertex_attrib_pointer_with_i32(
-            0, 2, WebGl::FLOAT, false,
-            4 * FLOAT_SIZE as i32,
-            0,
-        );
-        gl.enable_vertex_attrib_array(1);
-        gl.vertex_attrib_pointer_with_i32(
-            1, 2, WebGl::FLOAT, false,
-            4 * FLOAT_SIZE as i32,
-            2 * FLOAT_SIZE as i32,
-        );
-        gl.active_texture(WebGl::TEXTURE0);
-        gl.uniform1i(tex.as_ref(), 0);
+        self.shader.bind();
+        self.shader.set("uModel", context.mod_matrix())?;
         //
-        gl.bind_vertex_array(None);
+        gl.bind_texture(WebGl::TEXTURE_2D, texture);
+        self.mesh.draw();
+        Ok(())
+    }
+}
 
-        //
-        Ok(Self { gl, pro, buf: buffer, vao, upm, uvm })
+#[cfg(test)]
+mod tests {
+    use crate::backend::{Backend, Call, Headless, Object, UniformValue};
+    use crate::engine::{Context, Renderable};
+    use crate::glm::{Mat4, Vec3};
+    use crate::shader::{ProgramCache, ShaderLibrary};
+    use super::*;
+
+    struct TestContext {
+        gl: Rc<dyn Backend>,
+        model: Mat4,
+        shaders: ShaderLibrary,
+        programs: ProgramCache,
     }
 
-    pub fn draw(&self, context: &dyn Context, texture: Option<&WebGlTexture>) {
-        let gl = context.gl().clone();
-        //
-        gl.use_program(self.pro.as_ref());
-        gl.bind_vertex_array(self.vao.as_ref());
+    impl Context for TestContext {
+        fn gl(&self) -> &Rc<dyn Backend> {
+            &self.gl
+        }
+
+        fn mod_matrix(&self) -> &Mat4 {
+            &self.model
+        }
+
+        fn shaders(&self) -> &ShaderLibrary {
+            &self.shaders
+        }
+
+        fn programs(&self) -> &ProgramCache {
+            &self.programs
+        }
+    }
+
+    fn context() -> (Rc<Headless>, TestContext) {
+        let headless = Rc::new(Headless::default());
+        let gl: Rc<dyn Backend> = headless.clone();
+        let context = TestContext {
+            programs: ProgramCache::wrap(gl.clone()),
+            gl,
+            model: Mat4::from_translation(&Vec3::wrap(1.0, 2.0, 3.0)),
+            shaders: ShaderLibrary::builtin(),
+        };
+        (headless, context)
+    }
+
+    #[test]
+    fn create_reflects_the_shader_and_uploads_the_mesh() {
+        let (headless, context) = context();
+        let quad = Quad::create(&context).unwrap();
+        assert_eq!(quad.shader.attribute("position").unwrap().location, 0);
+        assert_eq!(quad.shader.attribute("texcoord").unwrap().location, 1);
+        assert_eq!(quad.shader.uniform("uModel").unwrap().type_, WebGl::FLOAT_MAT4);
+        assert_eq!(quad.shader.uniform("uSampler").unwrap().type_, WebGl::SAMPLER_2D);
+        assert!(quad.shader.block("Frame").is_some());
         //
-        gl.uniform_matrix4fv_with_f32_array(
-            self.upm.as_ref(), false, context.pro_matrix(),
-        );
-        gl.uniform_matrix4fv_with_f32_array(
-            self.uvm.as_ref(), false, context.mod_matrix(),
-        );
+        let calls = headless.take_calls();
+        let sampler = quad.shader.uniform("uSampler").unwrap().location;
+        let vao = calls.iter().position(|c| matches!(c, Call::CreateVertexArray(_))).unwrap();
+        assert_eq!(calls[vao - 3..vao], [
+            Call::UseProgram(Some(*quad.shader.program())),
+            Call::Uniform(sampler, UniformValue::Int(0)),
+            Call::ActiveTexture(WebGl::TEXTURE0),
+        ]);
+        let vertices = backend::f32_bytes(&[
+            1.0, 1.0, 1.0, 0.0,
+            1.0, -1.0, 1.0, 1.0,
+            -1.0, -1.0, 0.0, 1.0,
+            -1.0, 1.0, 0.0, 0.0,
+        ]);
+        let indices = [0u16, 1, 2, 2, 3, 0].iter().flat_map(|i| i.to_le_bytes()).collect();
+        let (v, b, ib) = match (&calls[vao], &calls[vao + 2], &calls[vao + 11]) {
+            (Call::CreateVertexArray(v), Call::CreateBuffer(b), Call::CreateBuffer(ib)) => (*v, *b, *ib),
+            other => panic!("unexpected calls {:?}", other),
+        };
+        assert_eq!(calls[vao..], [
+            Call::CreateVertexArray(v),
+            Call::BindVertexArray(Some(v)),
+            Call::CreateBuffer(b),
+            Call::BindBuffer(WebGl::ARRAY_BUFFER, Some(b)),
+            Call::BufferData(WebGl::ARRAY_BUFFER, vertices, WebGl::STATIC_DRAW),
+            Call::BindBuffer(WebGl::ARRAY_BUFFER, Some(b)),
+            Call::EnableVertexAttribArray(0),
+            Call::VertexAttribPointer(0, 2, WebGl::FLOAT, false, 16, 0),
+            Call::BindBuffer(WebGl::ARRAY_BUFFER, Some(b)),
+            Call::EnableVertexAttribArray(1),
+            Call::VertexAttribPointer(1, 2, WebGl::FLOAT, false, 16, 8),
+            Call::CreateBuffer(ib),
+            Call::BindBuffer(WebGl::ELEMENT_ARRAY_BUFFER, Some(ib)),
+            Call::BufferData(WebGl::ELEMENT_ARRAY_BUFFER, indices, WebGl::STATIC_DRAW),
+            Call::BindVertexArray(None),
+            Call::BindBuffer(WebGl::ARRAY_BUFFER, None),
+            Call::BindBuffer(WebGl::ELEMENT_ARRAY_BUFFER, None),
+        ]);
+    }
+
+    #[test]
+    fn draw_sets_the_model_matrix_and_draws_indexed() {
+        let (headless, context) = context();
+        let quad = Quad::create(&context).unwrap();
+        let vao = headless.calls().iter()
+            .find_map(|c| match c {
+                Call::CreateVertexArray(v) => Some(*v),
+                _ => None,
+            })
+            .unwrap();
+        headless.take_calls();
         //
-        gl.bind_texture(WebGl::TEXTURE_2D, texture);
-        gl.draw_arrays(WebGl::TRIANGLES, 0, 6);
+        let texture = Texture(99);
+        quad.draw(&context, Some(&texture)).unwrap();
+        let model = quad.shader.uniform("uModel").unwrap().location;
+        assert_eq!(headless.take_calls(), [
+            Call::UseProgram(Some(*quad.shader.program())),
+            Call::Uniform(model, UniformValue::Mat4(context.model.to_cols_array())),
+            Call::BindTexture(WebGl::TEXTURE_2D, Some(texture)),
+            Call::BindVertexArray(Some(vao)),
+            Call::DrawElements(WebGl::TRIANGLES, 6, WebGl::UNSIGNED_SHORT, 0),
+            Call::BindVertexArray(None),
+        ]);
         //
-        gl.bind_vertex_array(None);
+        drop(quad);
+        assert!(headless.take_calls().contains(&Call::DeleteVertexArray(Some(vao))));
+        assert!(!headless.objects().values().any(|o| matches!(o, Object::VertexArray | Object::Buffer)));
+    }
+
+    #[test]
+    fn draw_returns_uniform_errors() {
+        let (headless, context) = context();
+        let mesh = Quad::create(&context).unwrap().mesh;
+        let vs = "#version 300 es\nin vec2 position;\nvoid main() {}\n";
+        let fs = "#version 300 es\nprecision highp float;\nout vec4 color;\nvoid main() {}\n";
+        let shader = Rc::new(ShaderProgram::create(&context.gl, vs, fs).unwrap());
+        let quad = Quad { shader, mesh };
+        headless.take_calls();
+        assert_eq!(quad.draw(&context, None), Err(UniformError::Unknown("uModel".to_owned())));
+        assert!(!headless.take_calls().iter().any(|c| matches!(c, Call::DrawElements(..))));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,312 @@
+//! Generators for common solids. All are centered on the origin with +Y
+//! up, and closed solids have outward facing normals.
+
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use crate::glm::{Vec2, Vec3};
+use super::Geometry;
+
+/// A `cols` x `rows` grid spanning `center ± right ± up`, facing `normal`
+/// (`right × up`).
+fn grid(g: &mut Geometry, center: Vec3, right: Vec3, up: Vec3, normal: Vec3, cols: u32, rows: u32) {
+    let base = g.vertex_count() as u32;
+    for j in 0..=rows {
+        for i in 0..=cols {
+            let s = i as f32 / cols as f32;
+            let t = j as f32 / rows as f32;
+            let position = center + right * (2.0 * s - 1.0) + up * (2.0 * t - 1.0);
+            g.vertex(position, normal, Vec2::wrap(s, 1.0 - t));
+        }
+    }
+    for j in 0..rows {
+        for i in 0..cols {
+            let a = base + j * (cols + 1) + i;
+            g.quad(a, a + 1, a + cols + 2, a + cols + 1);
+        }
+    }
+}
+
+/// Triangles between consecutive rings of `columns + 1` vertices, rows
+/// running top to bottom. Rings that collapse to a pole only emit the
+/// non-degenerate half of each quad.
+fn bands(g: &mut Geometry, base: u32, columns: u32, rows: u32, top_pole: bool, bottom_pole: bool) {
+    for j in 0..rows - 1 {
+        for i in 0..columns {
+            let a = base + j * (columns + 1) + i;
+            let b = a + columns + 1;
+            if !(top_pole && j == 0) {
+                g.triangle(a, b + 1, a + 1);
+            }
+            if !(bottom_pole && j == rows - 2) {
+                g.triangle(a, b, b + 1);
+            }
+        }
+    }
+}
+
+/// Point on the unit circle in the XZ plane, `angle` turning from +Z to +X.
+fn around(angle: f32) -> Vec3 {
+    Vec3::wrap(angle.sin(), 0.0, angle.cos())
+}
+
+/// Flat disc at height `y` facing up or down, UVs mapped from above.
+fn cap(g: &mut Geometry, radius: f32, y: f32, segments: u32, up: bool) {
+    let normal = Vec3::wrap(0.0, if up { 1.0 } else { -1.0 }, 0.0);
+    let center = g.vertex(Vec3::wrap(0.0, y, 0.0), normal, Vec2::wrap(0.5, 0.5));
+    for i in 0..=segments {
+        let d = around(i as f32 / segments as f32 * TAU);
+        g.vertex(d * radius + Vec3::wrap(0.0, y, 0.0), normal, Vec2::wrap(0.5 + d.x * 0.5, 0.5 - d.z * 0.5));
+    }
+    for i in 0..segments {
+        let (a, b) = (center + 1 + i, center + 2 + i);
+        if up { g.triangle(center, a, b) } else { g.triangle(center, b, a) }
+    }
+}
+
+/// Axis-aligned cube with `size` long edges and a full texture per face.
+pub fn cube(size: f32) -> Geometry {
+    let h = size * 0.5;
+    let x = Vec3::wrap(1.0, 0.0, 0.0);
+    let y = Vec3::wrap(0.0, 1.0, 0.0);
+    let z = Vec3::wrap(0.0, 0.0, 1.0);
+    let mut g = Geometry::default();
+    for (normal, right, up) in [(x, -z, y), (-x, z, y), (y, x, -z), (-y, x, z), (z, x, y), (-z, -x, y)] {
+        grid(&mut g, normal * h, right * h, up * h, normal, 1, 1);
+    }
+    g
+}
+
+/// Plane in XZ facing +Y, split into `cols` x `rows` cells.
+pub fn plane(width: f32, depth: f32, cols: u32, rows: u32) -> Geometry {
+    let mut g = Geometry::default();
+    grid(
+        &mut g,
+        Vec3::default(),
+        Vec3::wrap(width * 0.5, 0.0, 0.0),
+        Vec3::wrap(0.0, 0.0, -depth * 0.5),
+        Vec3::wrap(0.0, 1.0, 0.0),
+        cols.max(1),
+        rows.max(1),
+    );
+    g
+}
+
+/// Latitude/longitude sphere, `segments` around and `rings` pole to pole.
+/// The seam and poles have duplicated vertices so UVs don't wrap.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Geometry {
+    let (segments, rings) = (segments.max(3), rings.max(2));
+    let mut g = Geometry::default();
+    for j in 0..=rings {
+        let phi = j as f32 / rings as f32 * PI;
+        for i in 0..=segments {
+            let d = around(i as f32 / segments as f32 * TAU);
+            let normal = Vec3::wrap(d.x * phi.sin(), phi.cos(), d.z * phi.sin());
+            g.vertex(normal * radius, normal, Vec2::wrap(i as f32 / segments as f32, j as f32 / rings as f32));
+        }
+    }
+    bands(&mut g, 0, segments, rings + 1, true, true);
+    g
+}
+
+/// Icosahedron subdivided `subdivisions` times and pushed onto the sphere,
+/// evenly spread triangles without a seam. UVs are the spherical mapping.
+pub fn ico_sphere(radius: f32, subdivisions: u32) -> Geometry {
+    let t = (1.0 + 5f32.sqrt()) * 0.5;
+    let mut points: Vec<Vec3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ].iter().map(|(x, y, z)| Vec3::wrap(*x, *y, *z).normalize()).collect();
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                points.push(((points[a as usize] + points[b as usize]) * 0.5).normalize());
+                points.len() as u32 - 1
+            })
+        };
+        faces = faces.iter()
+            .flat_map(|&[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+    let mut g = Geometry::default();
+    for p in points {
+        let u = (p.x.atan2(p.z) / TAU).rem_euclid(1.0);
+        let v = p.y.clamp(-1.0, 1.0).acos() / PI;
+        g.vertex(p * radius, p, Vec2::wrap(u, v));
+    }
+    for [a, b, c] in faces {
+        g.triangle(a, b, c);
+    }
+    g
+}
+
+/// Capped cylinder along Y.
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> Geometry {
+    let segments = segments.max(3);
+    let h = height * 0.5;
+    let mut g = Geometry::default();
+    for (y, v) in [(h, 0.0), (-h, 1.0)] {
+        for i in 0..=segments {
+            let d = around(i as f32 / segments as f32 * TAU);
+            g.vertex(d * radius + Vec3::wrap(0.0, y, 0.0), d, Vec2::wrap(i as f32 / segments as f32, v));
+        }
+    }
+    bands(&mut g, 0, segments, 2, false, false);
+    cap(&mut g, radius, h, segments, true);
+    cap(&mut g, radius, -h, segments, false);
+    g
+}
+
+/// Cone along Y with its apex at `height / 2` and a capped base. The apex
+/// is split per segment so side normals stay smooth.
+pub fn cone(radius: f32, height: f32, segments: u32) -> Geometry {
+    let segments = segments.max(3);
+    let h = height * 0.5;
+    let mut g = Geometry::default();
+    for (j, y) in [h, -h].into_iter().enumerate() {
+        for i in 0..=segments {
+            let d = around(i as f32 / segments as f32 * TAU);
+            let normal = (d * height + Vec3::wrap(0.0, radius, 0.0)).normalize();
+            let position = d * radius * j as f32 + Vec3::wrap(0.0, y, 0.0);
+            g.vertex(position, normal, Vec2::wrap(i as f32 / segments as f32, j as f32));
+        }
+    }
+    bands(&mut g, 0, segments, 2, true, false);
+    cap(&mut g, radius, -h, segments, false);
+    g
+}
+
+/// Cylinder of `height` with hemispherical ends, `rings` per hemisphere.
+/// V runs along the profile so the texture isn't stretched over the caps.
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Geometry {
+    let (segments, rings) = (segments.max(3), rings.max(1));
+    let h = height * 0.5;
+    let length = PI * radius + height;
+    let mut g = Geometry::default();
+    for (offset, start, arc) in [(h, 0.0, 0.0), (-h, FRAC_PI_2, FRAC_PI_2 * radius + height)] {
+        for j in 0..=rings {
+            let phi = start + j as f32 / rings as f32 * FRAC_PI_2;
+            let v = (arc + (phi - start) * radius) / length;
+            for i in 0..=segments {
+                let d = around(i as f32 / segments as f32 * TAU);
+                let normal = Vec3::wrap(d.x * phi.sin(), phi.cos(), d.z * phi.sin());
+                let position = normal * radius + Vec3::wrap(0.0, offset, 0.0);
+                g.vertex(position, normal, Vec2::wrap(i as f32 / segments as f32, v));
+            }
+        }
+    }
+    bands(&mut g, 0, segments, 2 * rings + 2, true, true);
+    g
+}
+
+/// Ring of `radius` around Y swept by a tube of radius `tube`.
+pub fn torus(radius: f32, tube: f32, segments: u32, sides: u32) -> Geometry {
+    let (segments, sides) = (segments.max(3), sides.max(3));
+    let mut g = Geometry::default();
+    for i in 0..=segments {
+        let d = around(i as f32 / segments as f32 * TAU);
+        for j in 0..=sides {
+            let phi = j as f32 / sides as f32 * TAU;
+            let normal = d * phi.cos() + Vec3::wrap(0.0, phi.sin(), 0.0);
+            let position = d * radius + normal * tube;
+            g.vertex(position, normal, Vec2::wrap(i as f32 / segments as f32, j as f32 / sides as f32));
+        }
+    }
+    for i in 0..segments {
+        for j in 0..sides {
+            let a = i * (sides + 1) + j;
+            let b = a + sides + 1;
+            g.quad(a, b, b + 1, a + 1);
+        }
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shared invariants: unit normals, indices in range, UVs in [0, 1]
+    /// and triangles winding counter-clockwise around their normals.
+    fn check(g: &Geometry, vertices: usize, indices: usize) {
+        assert_eq!(g.vertex_count(), vertices);
+        assert_eq!(g.normals.len(), vertices);
+        assert_eq!(g.uvs.len(), vertices);
+        assert_eq!(g.indices.len(), indices);
+        for n in &g.normals {
+            assert!((n.length() - 1.0).abs() < 1e-5, "normal {:?} is not unit length", n);
+        }
+        for uv in &g.uvs {
+            assert!((0.0..=1.0).contains(&uv.x) && (0.0..=1.0).contains(&uv.y), "uv {:?}", uv);
+        }
+        for t in g.indices.chunks(3) {
+            assert!(t.iter().all(|i| (*i as usize) < vertices));
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| i as usize);
+            let face = (g.positions[b] - g.positions[a]).cross(&(g.positions[c] - g.positions[a]));
+            let normal = g.normals[a] + g.normals[b] + g.normals[c];
+            assert!(face.length() > 1e-7, "degenerate triangle {:?}", t);
+            assert!(face.dot(&normal) > 0.0, "triangle {:?} winds against its normals", t);
+        }
+    }
+
+    #[test]
+    fn cube_faces() {
+        let g = cube(2.0);
+        check(&g, 24, 36);
+        assert!(g.positions.iter().all(|p| p.x.abs() == 1.0 && p.y.abs() == 1.0 && p.z.abs() == 1.0));
+    }
+
+    #[test]
+    fn plane_grid() {
+        let g = plane(4.0, 2.0, 4, 3);
+        check(&g, 20, 72);
+        assert!(g.positions.iter().all(|p| p.y == 0.0 && p.x.abs() <= 2.0 && p.z.abs() <= 1.0));
+    }
+
+    #[test]
+    fn spheres_on_radius() {
+        let g = uv_sphere(2.0, 16, 8);
+        check(&g, 17 * 9, 6 * 16 * 7);
+        let ico = ico_sphere(2.0, 2);
+        check(&ico, 10 * 16 + 2, 3 * 20 * 16);
+        for p in g.positions.iter().chain(ico.positions.iter()) {
+            assert!((p.length() - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn cylinder_and_cone() {
+        check(&cylinder(1.0, 2.0, 12), 26 + 2 * 14, 72 + 72);
+        check(&cone(1.0, 2.0, 12), 26 + 14, 36 + 36);
+    }
+
+    #[test]
+    fn capsule_profile() {
+        let g = capsule(0.5, 1.0, 12, 4);
+        check(&g, 10 * 13, 12 * 12 * 4);
+        for p in &g.positions {
+            let axis = Vec3::wrap(0.0, p.y.clamp(-0.5, 0.5), 0.0);
+            assert!(((*p - axis).length() - 0.5).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn torus_tube() {
+        let g = torus(2.0, 0.5, 16, 8);
+        check(&g, 17 * 9, 16 * 8 * 6);
+        for p in &g.positions {
+            let ring = Vec3::wrap(p.x, 0.0, p.z).normalize() * 2.0;
+            assert!(((*p - ring).length() - 0.5).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use crate::glm::{Vec2, Vec3};
+use super::parse_error::{floats, statements};
+use super::{Geometry, ParseError};
+
+/// The faces of one group drawn with one material.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ObjMesh {
+    pub name: String,
+    pub material: Option<String>,
+    pub geometry: Geometry,
+}
+
+/// A parsed Wavefront OBJ file, split into one mesh per group and
+/// material in order of first use. Faces without normals get them from
+/// their smoothing group, or flat ones with `s off`.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    /// Files named by `mtllib`, relative to the OBJ file.
+    pub material_libraries: Vec<String>,
+}
+
+/// Zero-based position, texture coordinate and normal indices.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct Face {
+    mesh: usize,
+    smoothing: u32,
+    corners: Vec<Corner>,
+}
+
+/// What a corner without an explicit normal shares its normal with.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Shading {
+    Explicit(usize),
+    Smooth(u32),
+    Flat(usize),
+}
+
+/// Corners with the same key share one vertex of their mesh.
+type VertexKey = (usize, Option<usize>, Shading);
+
+impl ObjModel {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut positions: Vec<Vec3> = Vec::new();
+        let mut uvs: Vec<Vec2> = Vec::new();
+        let mut normals: Vec<Vec3> = Vec::new();
+        let mut model = ObjModel::default();
+        let mut faces: Vec<Face> = Vec::new();
+        let mut name = String::from("default");
+        let mut material: Option<String> = None;
+        let mut smoothing = 0;
+        for (line, text) in statements(source) {
+            let mut words = text.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            let args: Vec<&str> = words.collect();
+            match keyword {
+                "v" => {
+                    let [x, y, z] = floats(line, keyword, &args, [None, None, None])?;
+                    positions.push(Vec3::wrap(x, y, z));
+                }
+                "vt" => {
+                    // OBJ puts v = 0 at the bottom of the image.
+                    let [u, v] = floats(line, keyword, &args, [None, Some(0.0)])?;
+                    uvs.push(Vec2::wrap(u, 1.0 - v));
+                }
+                "vn" => {
+                    let [x, y, z] = floats(line, keyword, &args, [None, None, None])?;
+                    normals.push(Vec3::wrap(x, y, z).normalize());
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(ParseError::wrap(line, "a face needs at least 3 vertices"));
+                    }
+                    let corners = args
+                        .iter()
+                        .map(|arg| corner(line, arg, positions.len(), uvs.len(), normals.len()))
+                        .collect::<Result<Vec<Corner>, ParseError>>()?;
+                    let key = (name.as_str(), material.as_deref());
+                    let mesh = match model.meshes.iter().position(|m| (m.name.as_str(), m.material.as_deref()) == key) {
+                        Some(mesh) => mesh,
+                        None => {
+                            model.meshes.push(ObjMesh {
+                                name: name.clone(),
+                                material: material.clone(),
+                                geometry: Geometry::default(),
+                            });
+                            model.meshes.len() - 1
+                        }
+                    };
+                    faces.push(Face { mesh, smoothing, corners });
+                }
+                "g" | "o" => {
+                    name = if args.is_empty() { String::from("default") } else { args.join(" ") };
+                }
+                "usemtl" => {
+                    material = if args.is_empty() { None } else { Some(args.join(" ")) };
+                }
+                "mtllib" => model.material_libraries.extend(args.iter().map(|s| (*s).to_owned())),
+                "s" => {
+                    smoothing = match args.first() {
+                        Some(&"off") => 0,
+                        Some(arg) => arg.parse().map_err(|_| {
+                            ParseError::wrap(line, format!("'s' expects a group number or 'off', got '{}'", arg))
+                        })?,
+                        None => return Err(ParseError::wrap(line, "'s' needs a group number or 'off'")),
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        //
+        let face_normals: Vec<Vec3> = faces
+            .iter()
+            .map(|face| newell(&face.corners.iter().map(|c| positions[c.0]).collect::<Vec<Vec3>>()))
+            .collect();
+        let mut smooth: HashMap<(usize, u32), Vec3> = HashMap::new();
+        for (face, normal) in faces.iter().zip(&face_normals) {
+            for corner in face.corners.iter().filter(|c| c.2.is_none() && face.smoothing != 0) {
+                *smooth.entry((corner.0, face.smoothing)).or_default() += *normal;
+            }
+        }
+
+        //
+        let mut vertices: Vec<HashMap<VertexKey, u32>> = vec![HashMap::new(); model.meshes.len()];
+        for (i, face) in faces.iter().enumerate() {
+            let geometry = &mut model.meshes[face.mesh].geometry;
+            let indices: Vec<u32> = face
+                .corners
+                .iter()
+                .map(|&(p, t, n)| {
+                    let shading = match n {
+                        Some(n) => Shading::Explicit(n),
+                        None if face.smoothing != 0 => Shading::Smooth(face.smoothing),
+                        None => Shading::Flat(i),
+                    };
+                    *vertices[face.mesh].entry((p, t, shading)).or_insert_with(|| {
+                        let normal = match shading {
+                            Shading::Explicit(n) => normals[n],
+                            Shading::Smooth(group) => smooth[&(p, group)].normalize(),
+                            Shading::Flat(_) => face_normals[i].normalize(),
+                        };
+                        let uv = t.map(|t| uvs[t]).unwrap_or_default();
+                        geometry.vertex(positions[p], normal, uv)
+                    })
+                })
+                .collect();
+            let points: Vec<Vec3> = face.corners.iter().map(|c| positions[c.0]).collect();
+            for [a, b, c] in triangulate(&points, face_normals[i]) {
+                geometry.triangle(indices[a], indices[b], indices[c]);
+            }
+        }
+        Ok(model)
+    }
+}
+
+/// Resolves one `v`, `v/vt`, `v//vn` or `v/vt/vn` face corner against the
+/// element counts seen so far.
+fn corner(line: usize, arg: &str, positions: usize, uvs: usize, normals: usize) -> Result<Corner, ParseError> {
+    let mut parts = arg.split('/');
+    let mut index = |kind: &str, count: usize| -> Result<Option<usize>, ParseError> {
+        let part = match parts.next() {
+            Some(part) if !part.is_empty() => part,
+            _ => return Ok(None),
+        };
+        let i: i64 = part.parse().map_err(|_| {
+            ParseError::wrap(line, format!("malformed face vertex '{}'", arg))
+        })?;
+        // Positive indices count from 1, negative ones back from the end.
+        let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+        if i == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(ParseError::wrap(
+                line,
+                format!("{} index {} out of range ({} defined)", kind, i, count),
+            ));
+        }
+        Ok(Some(resolved as usize))
+    };
+    let position = index("position", positions)?
+        .ok_or_else(|| ParseError::wrap(line, format!("face vertex '{}' has no position", arg)))?;
+    let uv = index("texture coordinate", uvs)?;
+    let normal = index("normal", normals)?;
+    Ok((position, uv, normal))
+}
+
+/// Normal of a possibly non-planar polygon, its length is twice the area.
+fn newell(points: &[Vec3]) -> Vec3 {
+    let mut normal = Vec3::default();
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal += a.cross(&b);
+    }
+    normal
+}
+
+/// Splits a polygon into triangles by ear clipping in the plane facing
+/// `normal`, keeping its winding. Falls back to a fan when no ear is left,
+/// as happens for self-intersecting input.
+fn triangulate(points: &[Vec3], normal: Vec3) -> Vec<[usize; 3]> {
+    if points.len() == 3 {
+        return vec![[0, 1, 2]];
+    }
+    // Project along the dominant axis, flipping so the polygon runs
+    // counter-clockwise in 2D.
+    let (ax, ay, az) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+    let flat: Vec<Vec2> = points
+        .iter()
+        .map(|p| {
+            if az >= ax && az >= ay {
+                Vec2::wrap(p.x, p.y * normal.z.signum())
+            } else if ax >= ay {
+                Vec2::wrap(p.y, p.z * normal.x.signum())
+            } else {
+                Vec2::wrap(p.z, p.x * normal.y.signum())
+            }
+        })
+        .collect();
+    let turn = |a: Vec2, b: Vec2, c: Vec2| (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut out = Vec::with_capacity(points.len() - 2);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+            let (pa, pb, pc) = (flat[a], flat[b], flat[c]);
+            turn(pa, pb, pc) > 0.0
+                && remaining.iter().filter(|&&j| j != a && j != b && j != c).all(|&j| {
+                    let p = flat[j];
+                    turn(pa, pb, p) < 0.0 || turn(pb, pc, p) < 0.0 || turn(pc, pa, p) < 0.0
+                })
+        });
+        match ear {
+            Some(i) => {
+                out.push([remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+                remaining.remove(i);
+            }
+            None => {
+                out.extend((1..n - 1).map(|i| [remaining[0], remaining[i], remaining[i + 1]]));
+                return out;
+            }
+        }
+    }
+    out.push([remaining[0], remaining[1], remaining[2]]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+    fn geometry(source: &str) -> Geometry {
+        let mut model = ObjModel::parse(source).unwrap();
+        assert_eq!(model.meshes.len(), 1);
+        model.meshes.remove(0).geometry
+    }
+
+    fn error(source: &str) -> ParseError {
+        ObjModel::parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn negative_indices_count_back() {
+        let relative = geometry(&format!("{}f -3 -2 -1\n", TRIANGLE));
+        assert_eq!(relative, geometry(&format!("{}f 1 2 3\n", TRIANGLE)));
+        assert_eq!(relative.normals[0], Vec3::wrap(0.0, 0.0, 1.0));
+        let e = error(&format!("{}f -4 -2 -1\n", TRIANGLE));
+        assert_eq!((e.line, e.message.as_str()), (4, "position index -4 out of range (3 defined)"));
+    }
+
+    #[test]
+    fn corner_forms() {
+        let normals = geometry(&format!("{}vn 0 0 -1\nf 1//1 2//1 3//1\n", TRIANGLE));
+        assert!(normals.normals.iter().all(|n| *n == Vec3::wrap(0.0, 0.0, -1.0)));
+        assert!(normals.uvs.iter().all(|uv| *uv == Vec2::default()));
+        //
+        let uvs = geometry(&format!("{}vt 0 0\nvt 1 0.25\nf 1/1 2/2 3/1\n", TRIANGLE));
+        assert_eq!(uvs.uvs, [Vec2::wrap(0.0, 1.0), Vec2::wrap(1.0, 0.75), Vec2::wrap(0.0, 1.0)]);
+        assert_eq!(uvs.normals[1], Vec3::wrap(0.0, 0.0, 1.0));
+        //
+        assert_eq!(error(&format!("{}f 1//2 2 3\n", TRIANGLE)).message, "normal index 2 out of range (0 defined)");
+        assert_eq!(error(&format!("{}f 1/x 2 3\n", TRIANGLE)).message, "malformed face vertex '1/x'");
+        assert_eq!(error(&format!("{}f /1 2 3\n", TRIANGLE)).message, "face vertex '/1' has no position");
+    }
+
+    #[test]
+    fn concave_faces_are_ear_clipped() {
+        // An L shape, a fan from the first corner would cover the notch.
+        let points = [(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)];
+        let mut source: String = points.iter().map(|(x, y)| format!("v {} {} 0\n", x, y)).collect();
+        source.push_str("f 1 2 3 4 5 6\n");
+        let geometry = geometry(&source);
+        assert_eq!(geometry.triangle_count(), 4);
+        let mut area = 0.0;
+        for t in geometry.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| geometry.positions[t[i] as usize]);
+            let cross = (b - a).cross(&(c - a));
+            assert!(cross.z > 0.0, "{:?} winds clockwise", t);
+            area += cross.z / 2.0;
+            // No triangle reaches into the notch at (1.5, 1.5).
+            let center = (a + b + c) / 3.0;
+            assert!(center.x <= 1.0 || center.y <= 1.0);
+        }
+        assert!((area - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn smoothing_groups_share_normals() {
+        // Two triangles folded along the x axis.
+        let fold = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 -1 1\nf 1 2 3\nf 2 1 4\n";
+        let smooth = geometry(&format!("s 1\n{}", fold));
+        assert_eq!(smooth.vertex_count(), 4);
+        // Face normals are weighted by area.
+        let shared = Vec3::wrap(0.0, 1.0, 2.0).normalize();
+        assert!((smooth.normals[0] - shared).length() < 1e-5);
+        assert_eq!(smooth.normals[0], smooth.normals[1]);
+        //
+        let flat = geometry(&format!("s off\n{}", fold));
+        assert_eq!(flat.vertex_count(), 6);
+        assert_eq!(flat.normals[0], Vec3::wrap(0.0, 0.0, 1.0));
+        assert_ne!(flat.normals[0], flat.normals[3]);
+        // Different groups do not blend.
+        let split = geometry("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 -1 1\ns 1\nf 1 2 3\ns 2\nf 2 1 4\n");
+        assert_eq!(split.vertex_count(), 6);
+        assert_eq!(error("s soft\n").message, "'s' expects a group number or 'off', got 'soft'");
+    }
+
+    #[test]
+    fn meshes_split_by_group_and_material() {
+        let model = ObjModel::parse(&format!(
+            "mtllib a.mtl b.mtl\n{}usemtl red\nf 1 2 3\ng lid\nf 1 2 3\nusemtl blue\nf 1 2 3\ng\nusemtl red\nf 3 2 1\n",
+            TRIANGLE,
+        )).unwrap();
+        let keys: Vec<(&str, Option<&str>, usize)> = model.meshes.iter()
+            .map(|m| (m.name.as_str(), m.material.as_deref(), m.geometry.triangle_count()))
+            .collect();
+        assert_eq!(keys, [("default", Some("red"), 2), ("lid", Some("red"), 1), ("lid", Some("blue"), 1)]);
+        assert_eq!(model.material_libraries, ["a.mtl", "b.mtl"]);
+    }
+
+    #[test]
+    fn errors_report_the_line_a_statement_starts_on() {
+        let source = "# header\nv 0 0 \\\n  0\nv 1 0 0\n\nv 0 1 0 # top\nf 1 2 \\\n 4\n";
+        let e = error(source);
+        assert_eq!((e.line, e.message.as_str()), (7, "position index 4 out of range (3 defined)"));
+        assert_eq!(e.to_string(), "line 7: position index 4 out of range (3 defined)");
+        // Continued lines still count towards later line numbers.
+        let e = error("v 0 \\\n0 \\\n0\nv 1 0\n");
+        assert_eq!((e.line, e.message.as_str()), (4, "'v' needs at least 3 values"));
+        assert_eq!(error("v 1 0 0\nvn 0 up 0\n").to_string(), "line 2: 'vn' expects numbers, got 'up'");
+        assert_eq!(error(&format!("{}f 1 2\n", TRIANGLE)).line, 4);
+    }
+}
@@ -0,0 +1,262 @@
+use super::{Node, NodeId};
+use crate::glm::Mat4;
+
+#[derive(Default)]
+struct Slot {
+    generation: u32,
+    node: Option<Node>,
+}
+
+/// Node arena. Slots of removed nodes are reused, their generation is
+/// bumped first so a stale `NodeId` resolves to `None`.
+#[derive(Default)]
+pub struct Scene {
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+    roots: Vec<NodeId>,
+}
+
+impl Scene {
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.slots.get(id.index)
+            .filter(|s| s.generation == id.generation)?
+            .node
+            .as_ref()
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.slots.get_mut(id.index)
+            .filter(|s| s.generation == id.generation)?
+            .node
+            .as_mut()
+    }
+
+    /// Number of live nodes.
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn roots(&self) -> &[NodeId] {
+        self.roots.as_slice()
+    }
+
+    /// Adds an empty node, under `parent` when it exists, otherwise as a root.
+    pub fn add(&mut self, parent: Option<NodeId>) -> NodeId {
+        let parent = parent.filter(|p| self.node(*p).is_some());
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.slots.push(Slot::default());
+            self.slots.len() - 1
+        });
+        let mut node = Node::default();
+        node.parent = parent;
+        let slot = &mut self.slots[index];
+        slot.node = Some(node);
+        let id = NodeId { index, generation: slot.generation };
+        self.link(id, parent);
+        id
+    }
+
+    /// Removes a node together with its whole subtree.
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let parent = match self.node(id) {
+            Some(node) => node.parent,
+            None => return false,
+        };
+        self.unlink(id, parent);
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let slot = &mut self.slots[id.index];
+            if let Some(node) = slot.node.take() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(id.index);
+                stack.extend(node.children);
+            }
+        }
+        true
+    }
+
+    /// Moves a node under a new parent, or to the roots with `None`.
+    /// Fails when either node is missing or the move would create a cycle.
+    pub fn reparent(&mut self, id: NodeId, parent: Option<NodeId>) -> bool {
+        let old = match self.node(id) {
+            Some(node) => node.parent,
+            None => return false,
+        };
+        if let Some(p) = parent {
+            if self.node(p).is_none() || self.is_ancestor(id, p) {
+                return false;
+            }
+        }
+        self.unlink(id, old);
+        self.link(id, parent);
+        let node = self.node_mut(id).unwrap();
+        node.parent = parent;
+        node.dirty = true;
+        true
+    }
+
+    /// True when `ancestor` is `id` itself or lies on its parent chain.
+    pub fn is_ancestor(&self, ancestor: NodeId, id: NodeId) -> bool {
+        let mut cur = Some(id);
+        while let Some(c) = cur {
+            if c == ancestor {
+                return true;
+            }
+            cur = self.node(c).and_then(|n| n.parent);
+        }
+        false
+    }
+
+    fn link(&mut self, id: NodeId, parent: Option<NodeId>) {
+        match parent {
+            Some(p) => self.node_mut(p).unwrap().children.push(id),
+            None => self.roots.push(id),
+        }
+    }
+
+    fn unlink(&mut self, id: NodeId, parent: Option<NodeId>) {
+        let list = match parent {
+            Some(p) => &mut self.node_mut(p).unwrap().children,
+            None => &mut self.roots,
+        };
+        list.retain(|c| *c != id);
+    }
+}
+
+impl Scene {
+    /// Recomputes world matrices of dirty nodes and everything below them.
+    pub fn update(&mut self) {
+        let mut stack: Vec<(NodeId, bool)> = self.roots.iter()
+            .rev()
+            .map(|id| (*id, false))
+            .collect();
+        while let Some((id, parent_dirty)) = stack.pop() {
+            let node = self.node(id).unwrap();
+            let dirty = node.dirty || parent_dirty;
+            if dirty {
+                let local = node.local_matrix();
+                let world = match node.parent {
+                    Some(p) => &self.node(p).unwrap().world * &local,
+                    None => local,
+                };
+                let node = self.node_mut(id).unwrap();
+                node.world = world;
+                node.dirty = false;
+            }
+            let node = self.node(id).unwrap();
+            stack.extend(node.children.iter().rev().map(|c| (*c, dirty)));
+        }
+    }
+
+    /// Depth-first, parents before children, in insertion order.
+    pub fn traverse<F>(&self, mut visit: F)
+    where
+        F: FnMut(NodeId, &Node),
+    {
+        let mut stack: Vec<NodeId> = self.roots.iter().rev().copied().collect();
+        while let Some(id) = stack.pop() {
+            let node = self.node(id).unwrap();
+            visit(id, node);
+            stack.extend(node.children.iter().rev());
+        }
+    }
+
+    /// World matrix as of the last `update`.
+    pub fn world_matrix(&self, id: NodeId) -> Option<&Mat4> {
+        self.node(id).map(|n| &n.world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glm::{Quat, Vec3};
+
+    fn close(a: &Mat4, b: &Mat4) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-5)
+    }
+
+    /// root -> (a -> c, b)
+    fn tree() -> (Scene, [NodeId; 4]) {
+        let mut scene = Scene::default();
+        let root = scene.add(None);
+        let a = scene.add(Some(root));
+        let b = scene.add(Some(root));
+        let c = scene.add(Some(a));
+        (scene, [root, a, b, c])
+    }
+
+    #[test]
+    fn parent_changes_reach_descendants() {
+        let (mut scene, [root, a, b, c]) = tree();
+        scene.node_mut(a).unwrap().set_translation(Vec3::wrap(0.0, 1.0, 0.0));
+        scene.node_mut(c).unwrap().set_scale(Vec3::wrap(2.0, 2.0, 2.0));
+        scene.update();
+        let c_world = Mat4::from_translation(&Vec3::wrap(0.0, 1.0, 0.0)) * Mat4::from_scale(&Vec3::wrap(2.0, 2.0, 2.0));
+        assert!(close(scene.world_matrix(c).unwrap(), &c_world));
+        //
+        let spin = Quat::from_axis_angle(&Vec3::wrap(0.0, 0.0, 1.0), 0.5);
+        scene.node_mut(root).unwrap().set_rotation(spin);
+        scene.update();
+        let root_world = Mat4::from_quat(&spin);
+        assert!(close(scene.world_matrix(b).unwrap(), &root_world));
+        assert!(close(scene.world_matrix(c).unwrap(), &(&root_world * &c_world)));
+        assert!(scene.traverse_ids().iter().all(|id| !scene.node(*id).unwrap().dirty));
+    }
+
+    #[test]
+    fn reparent_rejects_cycles() {
+        let (mut scene, [root, a, b, c]) = tree();
+        assert!(!scene.reparent(a, Some(c)));
+        assert!(!scene.reparent(a, Some(a)));
+        assert!(!scene.reparent(root, Some(b)));
+        assert_eq!(scene.node(a).unwrap().parent(), Some(root));
+        //
+        assert!(scene.reparent(c, Some(b)));
+        assert_eq!(scene.node(b).unwrap().children(), [c]);
+        assert!(scene.node(a).unwrap().children().is_empty());
+        assert!(scene.reparent(a, None));
+        assert_eq!(scene.roots(), [root, a]);
+    }
+
+    #[test]
+    fn remove_drops_the_subtree_and_reuses_slots() {
+        let (mut scene, [root, a, b, c]) = tree();
+        assert!(scene.remove(a));
+        assert!(!scene.remove(a));
+        assert!(scene.node(a).is_none() && scene.node(c).is_none());
+        assert_eq!(scene.node(root).unwrap().children(), [b]);
+        assert_eq!(scene.len(), 2);
+        //
+        let d = scene.add(Some(b));
+        let e = scene.add(None);
+        assert_eq!(scene.slots.len(), 4);
+        assert!(d != a && d != c && e != a && e != c);
+        // Stale ids do not alias the nodes now living in their slots.
+        assert!(scene.node(a).is_none() && scene.node(c).is_none());
+        assert!(!scene.reparent(c, Some(e)));
+        assert_eq!(scene.node(d).unwrap().parent(), Some(b));
+        assert_eq!(scene.len(), 4);
+    }
+
+    #[test]
+    fn traverse_visits_parents_first() {
+        let (mut scene, [root, a, b, c]) = tree();
+        let other = scene.add(None);
+        assert_eq!(scene.traverse_ids(), [root, a, c, b, other]);
+        scene.reparent(a, Some(b));
+        assert_eq!(scene.traverse_ids(), [root, b, a, c, other]);
+    }
+
+    impl Scene {
+        fn traverse_ids(&self) -> Vec<NodeId> {
+            let mut ids = Vec::new();
+            self.traverse(|id, _| ids.push(id));
+            ids
+        }
+    }
+}
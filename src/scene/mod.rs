@@ -0,0 +1,5 @@
+mod graph;
+mod node;
+
+pub use graph::Scene;
+pub use node::{Node, NodeId};
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+use crate::glm::{Mat4, Quat, Vec3};
+use crate::engine::Renderable;
+
+/// Slot index plus the generation of the slot, so ids of removed nodes
+/// stay invalid once their slot is reused.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId {
+    pub(super) index: usize,
+    pub(super) generation: u32,
+}
+
+pub struct Node {
+    translation: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+    pub(super) parent: Option<NodeId>,
+    pub(super) children: Vec<NodeId>,
+    pub(super) world: Mat4,
+    pub(super) dirty: bool,
+    /// What gets drawn with this node's world matrix, if anything.
+    pub renderable: Option<Rc<dyn Renderable>>,
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::default(),
+            rotation: Quat::default(),
+            scale: Vec3::wrap(1.0, 1.0, 1.0),
+            parent: None,
+            children: Vec::new(),
+            world: Mat4::default(),
+            dirty: true,
+            renderable: None,
+        }
+    }
+}
+
+impl Node {
+    pub fn translation(&self) -> Vec3 {
+        self.translation
+    }
+
+    pub fn rotation(&self) -> Quat {
+        self.rotation
+    }
+
+    pub fn scale(&self) -> Vec3 {
+        self.scale
+    }
+
+    pub fn set_translation(&mut self, translation: Vec3) {
+        self.translation = translation;
+        self.dirty = true;
+    }
+
+    pub fn set_rotation(&mut self, rotation: Quat) {
+        self.rotation = rotation;
+        self.dirty = true;
+    }
+
+    pub fn set_scale(&mut self, scale: Vec3) {
+        self.scale = scale;
+        self.dirty = true;
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        self.children.as_slice()
+    }
+
+    pub fn local_matrix(&self) -> Mat4 {
+        Mat4::from_trs(&self.translation, &self.rotation, &self.scale)
+    }
+
+    /// World matrix as of the last `Scene::update`.
+    pub fn world_matrix(&self) -> &Mat4 {
+        &self.world
+    }
+}
@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::{Rc, Weak};
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::Backend;
+use super::program::CompiledShader;
+use super::{ShaderError, ShaderProgram, Source};
+
+/// Shares linked programs between everything built from the same
+/// sources, and compiled stages between programs, so a vertex shader
+/// used with several fragment shaders compiles once. Both are keyed by a
+/// hash of their code, the code itself only settles collisions. Entries
+/// are weak, GL objects are deleted when the last `Rc` handed out drops.
+/// Uniform blocks registered with `bind_block` are attached to their
+/// binding in every program linked here.
+pub struct ProgramCache {
+    gl: Rc<dyn Backend>,
+    /// By vertex and fragment code hash.
+    programs: Entries<(u64, u64), ShaderProgram>,
+    /// By shader type and code hash.
+    stages: Entries<(u32, u64), CompiledShader>,
+    blocks: RefCell<HashMap<String, u32>>,
+}
+
+/// Live objects under a code hash, more than one only on a collision.
+type Entries<K, T> = RefCell<HashMap<K, Vec<Weak<T>>>>;
+
+fn hash(code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Drops dead entries and keys left without any.
+fn prune<K, T>(map: &mut HashMap<K, Vec<Weak<T>>>) {
+    map.retain(|_, entries| {
+        entries.retain(|e| e.strong_count() > 0);
+        !entries.is_empty()
+    });
+}
+
+impl ProgramCache {
+    pub fn wrap(gl: Rc<dyn Backend>) -> Self {
+        Self {
+            gl,
+            programs: RefCell::new(HashMap::new()),
+            stages: RefCell::new(HashMap::new()),
+            blocks: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl ProgramCache {
+    /// The program linked from `vs` and `fs`, compiled only if no live
+    /// program was built from the same code. Stages still used by other
+    /// programs are reused.
+    pub fn get(&self, vs: &Source, fs: &Source) -> Result<Rc<ShaderProgram>, ShaderError> {
+        let key = (hash(&vs.code), hash(&fs.code));
+        if let Some(program) = self.find(key, vs, fs) {
+            return Ok(program);
+        }
+        let v_shader = self.stage(WebGl::VERTEX_SHADER, key.0, vs)?;
+        let f_shader = self.stage(WebGl::FRAGMENT_SHADER, key.1, fs)?;
+        let program = Rc::new(ShaderProgram::link(&self.gl, v_shader, f_shader)?);
+        for (name, binding) in self.blocks.borrow().iter() {
+            program.bind_block(name, *binding);
+        }
+        let mut programs = self.programs.borrow_mut();
+        prune(&mut programs);
+        programs.entry(key).or_default().push(Rc::downgrade(&program));
+        Ok(program)
+    }
+
+    fn find(&self, key: (u64, u64), vs: &Source, fs: &Source) -> Option<Rc<ShaderProgram>> {
+        self.programs.borrow()
+            .get(&key)?
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|p| match p.stages() {
+                [v, f] => v.code == vs.code && f.code == fs.code,
+                _ => false,
+            })
+    }
+
+    /// The compiled `type_` shader for `source`, shared while in use.
+    fn stage(&self, type_: u32, hash: u64, source: &Source) -> Result<Rc<CompiledShader>, ShaderError> {
+        let key = (type_, hash);
+        let live = self.stages.borrow()
+            .get(&key)
+            .and_then(|entries| {
+                entries.iter().filter_map(Weak::upgrade).find(|s| s.code == source.code)
+            });
+        if let Some(stage) = live {
+            return Ok(stage);
+        }
+        let stage = Rc::new(CompiledShader::create(&self.gl, type_, source)?);
+        let mut stages = self.stages.borrow_mut();
+        prune(&mut stages);
+        stages.entry(key).or_default().push(Rc::downgrade(&stage));
+        Ok(stage)
+    }
+
+    /// Binds the block `name` to `binding` in live and future programs.
+    pub fn bind_block(&self, name: &str, binding: u32) {
+        self.blocks.borrow_mut().insert(name.to_owned(), binding);
+        for program in self.programs.borrow().values().flatten().filter_map(Weak::upgrade) {
+            program.bind_block(name, binding);
+        }
+    }
+
+    /// Number of programs still in use.
+    pub fn len(&self) -> usize {
+        self.programs.borrow().values().flatten().filter(|p| p.strong_count() > 0).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{Call, Headless};
+    use super::*;
+
+    const VS: &str = "#version 300 es\nin vec2 position;\nvoid main() {}\n";
+    const FS: &str = "#version 300 es\nprecision highp float;\nout vec4 color;\nvoid main() {}\n";
+
+    #[test]
+    fn programs_are_shared_by_source() {
+        let headless = Rc::new(Headless::default());
+        let cache = ProgramCache::wrap(headless.clone());
+        let (vs, fs) = (Source::plain("vs", VS), Source::plain("fs", FS));
+        let a = cache.get(&vs, &fs).unwrap();
+        let b = cache.get(&vs, &fs).unwrap();
+        assert!(Rc::ptr_eq(&a, &b));
+        // Swapping a stage is a different program.
+        let other = Source::plain("fs", &FS.replace("color", "tint"));
+        let c = cache.get(&vs, &other).unwrap();
+        assert!(!Rc::ptr_eq(&a, &c));
+        assert_eq!(cache.len(), 2);
+        //
+        let program = *c.program();
+        headless.take_calls();
+        drop(c);
+        // The fragment stage goes with it, the vertex stage is still in use.
+        let calls = headless.take_calls();
+        assert!(matches!(
+            calls[..],
+            [Call::DeleteProgram(Some(p)), Call::DeleteShader(Some(_))] if p == program
+        ));
+        assert_eq!(cache.len(), 1);
+        drop((a, b));
+        assert!(cache.is_empty());
+        assert!(headless.objects().is_empty());
+    }
+
+    #[test]
+    fn shared_stages_compile_once() {
+        let headless = Rc::new(Headless::default());
+        let cache = ProgramCache::wrap(headless.clone());
+        let vs = Source::plain("vs", VS);
+        let fs: Vec<Source> = ["color", "tint", "albedo"].iter()
+            .map(|name| Source::plain("fs", &FS.replace("color", name)))
+            .collect();
+        let programs: Vec<_> = fs.iter().map(|fs| cache.get(&vs, fs).unwrap()).collect();
+        let compiled = headless.calls().into_iter()
+            .filter_map(|c| match c {
+                Call::CreateShader(type_, _) => Some(type_),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let (v, f) = (WebGl::VERTEX_SHADER, WebGl::FRAGMENT_SHADER);
+        assert_eq!(compiled, [v, f, f, f]);
+        assert_eq!(cache.len(), 3);
+        // Once every user is gone the stage is compiled anew.
+        drop(programs);
+        headless.take_calls();
+        let _program = cache.get(&vs, &fs[0]).unwrap();
+        let created = headless.calls().iter().filter(|c| matches!(c, Call::CreateShader(..))).count();
+        assert_eq!(created, 2);
+    }
+
+    #[test]
+    fn colliding_hashes_compare_the_code() {
+        let cache = ProgramCache::wrap(Rc::new(Headless::default()));
+        let (vs, fs) = (Source::plain("vs", VS), Source::plain("fs", FS));
+        let other = Source::plain("fs", &FS.replace("color", "tint"));
+        let a = cache.get(&vs, &fs).unwrap();
+        // File `a` under the key `b` will hash to, as a collision would.
+        let key = (hash(&vs.code), hash(&other.code));
+        cache.programs.borrow_mut().insert(key, vec![Rc::downgrade(&a)]);
+        let stage = a.stages()[1].clone();
+        cache.stages.borrow_mut().insert((WebGl::FRAGMENT_SHADER, key.1), vec![Rc::downgrade(&stage)]);
+        let b = cache.get(&vs, &other).unwrap();
+        assert!(!Rc::ptr_eq(&a, &b));
+        assert!(!Rc::ptr_eq(&b.stages()[1], &stage));
+        assert_eq!(b.stages()[1].code, other.code);
+        assert!(Rc::ptr_eq(&a.stages()[0], &b.stages()[0]));
+        assert!(Rc::ptr_eq(&cache.get(&vs, &other).unwrap(), &b));
+    }
+
+    #[test]
+    fn bind_block_reaches_live_programs() {
+        let headless = Rc::new(Headless::default());
+        let cache = ProgramCache::wrap(headless.clone());
+        let code = "layout(std140) uniform Lights\n{\n vec4 c;\n};\nuniform Frame {\n mat4 m;\n};\n";
+        let vs = Source::plain("vs", code);
+        let program = cache.get(&vs, &Source::plain("fs", FS)).unwrap();
+        assert_eq!((program.block("Lights"), program.block("Frame")), (Some(0), Some(1)));
+        headless.take_calls();
+        cache.bind_block("Frame", 3);
+        assert_eq!(headless.take_calls(), [Call::UniformBlockBinding(*program.program(), 1, 3)]);
+        // Later programs get it as they link.
+        let other = cache.get(&vs, &Source::plain("fs", &FS.replace("color", "tint"))).unwrap();
+        let calls = headless.take_calls();
+        assert!(calls.contains(&Call::UniformBlockBinding(*other.program(), 1, 3)));
+        assert!(!program.bind_block("Missing", 1));
+    }
+}
@@ -0,0 +1,148 @@
+use super::Source;
+
+/// One entry of a compiler info log, pointing at the original file and
+/// line when the driver reported one.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub warning: bool,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    /// Text of the offending line.
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    /// Splits an info log into entries. Understands the ANGLE/WebGL
+    /// `ERROR: 0:12: msg`, Mesa `0:12(5): error: msg` and NVIDIA
+    /// `0(12) : error C0000: msg` forms, other lines are kept as messages.
+    pub fn parse_log(log: &str, source: &Source) -> Vec<Self> {
+        log.lines()
+            .map(|l| l.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+            .filter(|l| !l.is_empty())
+            .map(|l| Self::parse(l, source))
+            .collect()
+    }
+
+    fn parse(text: &str, source: &Source) -> Self {
+        let mut rest = text;
+        let mut warning = false;
+        for (prefix, w) in [("ERROR:", false), ("WARNING:", true)] {
+            if let Some(r) = rest.strip_prefix(prefix) {
+                rest = r.trim_start();
+                warning = w;
+            }
+        }
+        let (line, column, rest) = match location(rest) {
+            Some((line, column, rest)) => (Some(line), column, rest),
+            None => (None, None, rest),
+        };
+        let mut message = rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+        for (prefix, w) in [("error", false), ("warning", true)] {
+            if let Some(r) = message.strip_prefix(prefix) {
+                // Drops vendor codes such as `C0000` before the colon.
+                if let Some((_, r)) = r.split_once(':') {
+                    message = r.trim_start();
+                    warning = w;
+                }
+            }
+        }
+        let origin = line.and_then(|l| source.origin(l));
+        Self {
+            warning,
+            file: origin.map(|(file, _)| file.to_owned()),
+            line: origin.map(|(_, n)| n).or(line),
+            column,
+            message: message.to_owned(),
+            snippet: line.and_then(|l| source.line(l)).map(str::to_owned),
+        }
+    }
+}
+
+fn number(s: &str) -> Option<(usize, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+/// `S:L:`, `S:L(C):` or `S(L) :` at the start of `s`.
+fn location(s: &str) -> Option<(usize, Option<usize>, &str)> {
+    let (_, rest) = number(s)?;
+    if let Some(rest) = rest.strip_prefix('(') {
+        let (line, rest) = number(rest)?;
+        let rest = rest.strip_prefix(')')?.trim_start().strip_prefix(':')?;
+        return Some((line, None, rest));
+    }
+    let (line, rest) = number(rest.strip_prefix(':')?)?;
+    let (column, rest) = match rest.strip_prefix('(') {
+        Some(rest) => {
+            let (column, rest) = number(rest)?;
+            (Some(column), rest.strip_prefix(')')?)
+        }
+        None => (None, rest),
+    };
+    Some((line, column, rest.strip_prefix(':')?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ShaderLibrary;
+    use super::*;
+
+    /// Lines: version, define, `a.glsl` 1-2, then `main.vert` 3-5.
+    fn source() -> Source {
+        let mut library = ShaderLibrary::default();
+        library.register("a.glsl", "float a;\nfloat b;");
+        let main = "#version 300 es\n#include \"a.glsl\"\nvoid main() {\n    x = 1.0;\n}";
+        library.preprocess("main.vert", main, &[("X", "1")]).unwrap()
+    }
+
+    fn fields(d: &Diagnostic) -> (Option<&str>, Option<usize>, Option<usize>, bool, Option<&str>) {
+        (d.file.as_deref(), d.line, d.column, d.warning, d.snippet.as_deref())
+    }
+
+    #[test]
+    fn parses_angle_logs() {
+        let log = "ERROR: 0:4: 'b' : redefinition\n\
+                   WARNING: 0:6: 'x' : implicit declaration\n\
+                   ERROR: 2 compilation errors.  No code generated.\n\n\0";
+        let ds = Diagnostic::parse_log(log, &source());
+        assert_eq!(ds.len(), 3);
+        assert_eq!(fields(&ds[0]), (Some("a.glsl"), Some(2), None, false, Some("float b;")));
+        assert_eq!(ds[0].message, "'b' : redefinition");
+        assert_eq!(fields(&ds[1]), (Some("main.vert"), Some(4), None, true, Some("    x = 1.0;")));
+        assert_eq!(ds[1].message, "'x' : implicit declaration");
+        assert_eq!(fields(&ds[2]), (None, None, None, false, None));
+        assert_eq!(ds[2].message, "2 compilation errors.  No code generated.");
+    }
+
+    #[test]
+    fn parses_mesa_logs() {
+        let log = "0:3(7): error: syntax error, unexpected IDENTIFIER\n\
+                   0:6(5): warning: `x' used uninitialized\n";
+        let ds = Diagnostic::parse_log(log, &source());
+        assert_eq!(fields(&ds[0]), (Some("a.glsl"), Some(1), Some(7), false, Some("float a;")));
+        assert_eq!(ds[0].message, "syntax error, unexpected IDENTIFIER");
+        assert_eq!(fields(&ds[1]), (Some("main.vert"), Some(4), Some(5), true, Some("    x = 1.0;")));
+        assert_eq!(ds[1].message, "`x' used uninitialized");
+    }
+
+    #[test]
+    fn parses_nvidia_logs() {
+        let log = "0(6) : error C1008: undefined variable \"x\"\n\
+                   0(2) : warning C7022: unrecognized profile specifier \"X\"\n";
+        let ds = Diagnostic::parse_log(log, &source());
+        assert_eq!(fields(&ds[0]), (Some("main.vert"), Some(4), None, false, Some("    x = 1.0;")));
+        assert_eq!(ds[0].message, "undefined variable \"x\"");
+        assert_eq!(fields(&ds[1]), (Some("<defines>"), Some(1), None, true, Some("#define X 1")));
+        assert_eq!(ds[1].message, "unrecognized profile specifier \"X\"");
+    }
+
+    #[test]
+    fn lines_past_the_source_keep_the_reported_number() {
+        let ds = Diagnostic::parse_log("ERROR: 0:42: '}' : unexpected end", &source());
+        assert_eq!(fields(&ds[0]), (None, Some(42), None, false, None));
+        assert_eq!(location("0:12 no colon"), None);
+        assert_eq!(location("0(12) error"), None);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::type_name;
+use super::Diagnostic;
+
+/// Why `ShaderProgram::set` refused a value.
+#[derive(Clone, PartialEq, Debug)]
+pub enum UniformError {
+    Unknown(String),
+    TypeMismatch {
+        name: String,
+        expected: u32,
+        found: u32,
+    },
+}
+
+impl fmt::Display for UniformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniformError::Unknown(name) => write!(f, "No active uniform named '{}'", name),
+            UniformError::TypeMismatch { name, expected, found } => write!(
+                f, "Uniform '{}' is declared {}, got {}",
+                name, type_name(*expected), type_name(*found),
+            ),
+        }
+    }
+}
+
+impl From<UniformError> for String {
+    fn from(e: UniformError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Why a program could not be built.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ShaderError {
+    Preprocess(String),
+    Create(String),
+    Compile {
+        stage: u32,
+        diagnostics: Vec<Diagnostic>,
+    },
+    Link(String),
+}
+
+fn stage_name(stage: u32) -> &'static str {
+    match stage {
+        WebGl::VERTEX_SHADER => "vertex",
+        WebGl::FRAGMENT_SHADER => "fragment",
+        _ => "unknown",
+    }
+}
+
+/// Renders a diagnostic the way rustc does, with the line and a caret
+/// under the column or the whole statement.
+fn annotate(f: &mut fmt::Formatter<'_>, d: &Diagnostic) -> fmt::Result {
+    let kind = if d.warning { "warning" } else { "error" };
+    let (line, snippet) = match (d.line, &d.snippet) {
+        (Some(line), Some(snippet)) => (line, snippet),
+        _ => return writeln!(f, "{}: {}", kind, d.message),
+    };
+    let file = d.file.as_deref().unwrap_or("<source>");
+    let gutter = " ".repeat(line.to_string().len());
+    let indent = snippet.len() - snippet.trim_start().len();
+    let (start, width) = match d.column {
+        Some(column) => (column.saturating_sub(1), 1),
+        None => (indent, snippet.trim().len().max(1)),
+    };
+    writeln!(f, "{}: {}", kind, d.message)?;
+    match d.column {
+        Some(column) => writeln!(f, "{}--> {}:{}:{}", gutter, file, line, column)?,
+        None => writeln!(f, "{}--> {}:{}", gutter, file, line)?,
+    }
+    writeln!(f, "{} |", gutter)?;
+    writeln!(f, "{} | {}", line, snippet.trim_end())?;
+    writeln!(f, "{} | {}{}", gutter, " ".repeat(start), "^".repeat(width))
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderError::Preprocess(e) => write!(f, "Shader preprocessing failed: {}", e),
+            ShaderError::Create(e) => write!(f, "{}", e),
+            ShaderError::Compile { stage, diagnostics } => {
+                writeln!(f, "The {} shader failed to compile", stage_name(*stage))?;
+                for d in diagnostics {
+                    annotate(f, d)?;
+                }
+                Ok(())
+            }
+            ShaderError::Link(log) => write!(f, "Program failed to link: {}", log.trim_end()),
+        }
+    }
+}
+
+impl From<ShaderError> for String {
+    fn from(e: ShaderError) -> Self {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ShaderLibrary, Source};
+    use super::*;
+
+    fn source() -> Source {
+        let mut library = ShaderLibrary::default();
+        library.register("a.glsl", "float a;\nfloat b;");
+        let main = "#version 300 es\n#include \"a.glsl\"\nvoid main() {\n    x = 1.0;\n}";
+        library.preprocess("main.vert", main, &[("X", "1")]).unwrap()
+    }
+
+    #[test]
+    fn compile_errors_point_at_the_source() {
+        let log = "ERROR: 0:6: 'x' : undeclared identifier\n\
+                   0:3(7): warning: unused variable\n\
+                   ERROR: 2 compilation errors.  No code generated.";
+        let error = ShaderError::Compile {
+            stage: WebGl::VERTEX_SHADER,
+            diagnostics: Diagnostic::parse_log(log, &source()),
+        };
+        assert_eq!(error.to_string(), "\
+The vertex shader failed to compile
+error: 'x' : undeclared identifier
+ --> main.vert:4
+  |
+4 |     x = 1.0;
+  |     ^^^^^^^^
+warning: unused variable
+ --> a.glsl:1:7
+  |
+1 | float a;
+  |       ^
+error: 2 compilation errors.  No code generated.
+");
+    }
+
+    #[test]
+    fn other_errors_display_their_message() {
+        let link = ShaderError::Link("missing main\n".to_owned());
+        assert_eq!(link.to_string(), "Program failed to link: missing main");
+        let stage = ShaderError::Compile { stage: WebGl::FRAGMENT_SHADER, diagnostics: Vec::new() };
+        assert_eq!(stage.to_string(), "The fragment shader failed to compile\n");
+        let uniform = UniformError::TypeMismatch {
+            name: "uModel".to_owned(),
+            expected: WebGl::FLOAT_MAT4,
+            found: WebGl::FLOAT,
+        };
+        assert_eq!(String::from(uniform), "Uniform 'uModel' is declared mat4, got float");
+    }
+}
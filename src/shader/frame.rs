@@ -0,0 +1,27 @@
+use crate::glm::{Mat4, Vec3};
+use super::{AsStd140, Std140};
+
+/// Per-frame values every program reads from the `Frame` block declared
+/// in `frame.glsl`.
+#[derive(Clone, Debug)]
+pub struct FrameUniforms {
+    pub projection: Mat4,
+    pub view: Mat4,
+    pub camera_position: Vec3,
+    /// Seconds since the page loaded.
+    pub time: f32,
+}
+
+impl FrameUniforms {
+    pub const BLOCK: &'static str = "Frame";
+    pub const BINDING: u32 = 0;
+}
+
+impl AsStd140 for FrameUniforms {
+    fn write_std140(&self, out: &mut Std140) {
+        out.mat4(&self.projection)
+            .mat4(&self.view)
+            .vec3(&self.camera_position)
+            .float(self.time);
+    }
+}
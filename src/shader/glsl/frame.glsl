@@ -0,0 +1,6 @@
+layout(std140) uniform Frame {
+    mat4 uProjection;
+    mat4 uView;
+    vec3 uCameraPosition;
+    float uTime;
+};
@@ -0,0 +1,14 @@
+#version 300 es
+precision highp float;
+in vec2 vTexCoord;
+#ifdef TEXTURED
+uniform sampler2D uSampler;
+#endif
+out vec4 outColor;
+void main() {
+#ifdef TEXTURED
+    outColor = texture(uSampler, vTexCoord);
+#else
+    outColor = vec4(1.0);
+#endif
+}
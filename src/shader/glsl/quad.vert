@@ -0,0 +1,10 @@
+#version 300 es
+precision highp float;
+#include "transform.glsl"
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 texcoord;
+out vec2 vTexCoord;
+void main() {
+    gl_Position = transform(vec3(position, 0.0));
+    vTexCoord = texcoord;
+}
@@ -0,0 +1,6 @@
+#include "frame.glsl"
+uniform mat4 uModel;
+
+vec4 transform(vec3 position) {
+    return uProjection * uView * uModel * vec4(position, 1.0);
+}
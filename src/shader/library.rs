@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use super::Source;
+
+const BUILTINS: &[(&str, &str)] = &[
+    ("frame.glsl", include_str!("glsl/frame.glsl")),
+    ("transform.glsl", include_str!("glsl/transform.glsl")),
+];
+
+/// Named GLSL sources that shaders pull in with `#include "name"`.
+#[derive(Default)]
+pub struct ShaderLibrary {
+    sources: HashMap<String, String>,
+}
+
+impl ShaderLibrary {
+    /// A library holding the engine's own chunks.
+    pub fn builtin() -> Self {
+        let mut library = Self::default();
+        for (name, source) in BUILTINS {
+            library.register(name, source);
+        }
+        library
+    }
+}
+
+impl ShaderLibrary {
+    pub fn register(&mut self, name: &str, source: &str) {
+        self.sources.insert(name.to_owned(), source.to_owned());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.sources.get(name).map(String::as_str)
+    }
+
+    /// Expands `#include "name"` (or `<name>`) recursively, pulling each
+    /// file in once, and adds a `#define` per entry of `defines` right
+    /// after the `#version` line. `name` is the file name `source` is
+    /// reported under, the injected lines come from `<defines>`.
+    pub fn preprocess(
+        &self,
+        name: &str,
+        source: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Source, String> {
+        let mut out = Source::empty();
+        let mut stack = vec![name.to_owned()];
+        let mut seen = Vec::new();
+        self.expand(name, source, &mut stack, &mut seen, &mut out)?;
+        //
+        let header: Vec<String> = defines.iter()
+            .map(|(key, value)| format!("#define {} {}", key, value))
+            .collect();
+        let first = out.code.lines().enumerate().find(|(_, l)| !l.trim().is_empty());
+        let at = match first {
+            Some((i, line)) if line.trim_start().starts_with("#version") => i + 1,
+            _ => 0,
+        };
+        out.insert(at, &header, "<defines>");
+        Ok(out)
+    }
+
+    fn expand(
+        &self,
+        name: &str,
+        source: &str,
+        stack: &mut Vec<String>,
+        seen: &mut Vec<String>,
+        out: &mut Source,
+    ) -> Result<(), String> {
+        for (i, line) in source.lines().enumerate() {
+            let target = match include_target(line) {
+                Some(target) => target.map_err(|e| format!("{}:{}: {}", name, i + 1, e))?,
+                None => {
+                    out.push(line, name, i + 1);
+                    continue;
+                }
+            };
+            if stack.iter().any(|s| s == target) {
+                return Err(format!("{}:{}: include cycle through '{}'", name, i + 1, target));
+            }
+            if seen.iter().any(|s| s == target) {
+                continue;
+            }
+            let included = self.get(target)
+                .ok_or_else(|| format!("{}:{}: unknown include '{}'", name, i + 1, target))?;
+            seen.push(target.to_owned());
+            stack.push(target.to_owned());
+            self.expand(target, included, stack, seen, out)?;
+            stack.pop();
+        }
+        Ok(())
+    }
+}
+
+/// `None` for lines that are not an `#include`.
+fn include_target(line: &str) -> Option<Result<&str, String>> {
+    let rest = line.trim().strip_prefix('#')?.trim_start().strip_prefix("include")?;
+    let rest = rest.trim();
+    let target = rest.strip_prefix('"').and_then(|r| r.strip_suffix('"'))
+        .or_else(|| rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')));
+    Some(target.ok_or_else(|| format!("malformed include '{}'", rest)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> ShaderLibrary {
+        let mut library = ShaderLibrary::default();
+        library.register("a.glsl", "// a\n#include \"b.glsl\"\nfloat a;");
+        library.register("b.glsl", "// b");
+        library.register("c.glsl", "#include <d.glsl>");
+        library.register("d.glsl", "\n#include \"c.glsl\"");
+        library
+    }
+
+    #[test]
+    fn defines_follow_the_version_line() {
+        let source = "\n#version 300 es\nvoid main() {}";
+        let out = library().preprocess("main", source, &[("LIT", "1"), ("N", "4")]).unwrap();
+        assert_eq!(out.code, "\n#version 300 es\n#define LIT 1\n#define N 4\nvoid main() {}\n");
+        // Without a `#version` they go first.
+        let out = library().preprocess("main", "float x;", &[("A", "1")]).unwrap();
+        assert_eq!(out.code, "#define A 1\nfloat x;\n");
+    }
+
+    #[test]
+    fn files_are_included_once() {
+        let source = "#version 300 es\n#include \"a.glsl\"\n#include <b.glsl>\n\
+                      #include \"a.glsl\"\nvoid main() {}";
+        let out = library().preprocess("main", source, &[]).unwrap();
+        assert_eq!(out.code, "#version 300 es\n// a\n// b\nfloat a;\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn bad_includes_report_file_and_line() {
+        let library = library();
+        let error = |source| library.preprocess("main", source, &[]).unwrap_err();
+        assert_eq!(error("x\n#include \"zz.glsl\""), "main:2: unknown include 'zz.glsl'");
+        assert_eq!(error("#include \"c.glsl\""), "d.glsl:2: include cycle through 'c.glsl'");
+        assert_eq!(error("#include zz"), "main:1: malformed include 'zz'");
+        assert_eq!(error("#include \"zz>"), "main:1: malformed include '\"zz>'");
+        let mut library = ShaderLibrary::default();
+        library.register("self.glsl", "#include \"self.glsl\"");
+        assert_eq!(
+            library.preprocess("self.glsl", "#include \"self.glsl\"", &[]).unwrap_err(),
+            "self.glsl:1: include cycle through 'self.glsl'",
+        );
+    }
+
+    #[test]
+    fn origins_map_through_includes() {
+        let source = "#version 300 es\n#include \"a.glsl\"\nvoid main() {}";
+        let out = library().preprocess("main.vert", source, &[("X", "1")]).unwrap();
+        let origins: Vec<_> = (1..=7).map(|line| out.origin(line)).collect();
+        assert_eq!(origins, [
+            Some(("main.vert", 1)),
+            Some(("<defines>", 1)),
+            Some(("a.glsl", 1)),
+            Some(("b.glsl", 1)),
+            Some(("a.glsl", 3)),
+            Some(("main.vert", 3)),
+            None,
+        ]);
+        assert_eq!(out.line(5), Some("float a;"));
+        assert_eq!(out.origin(0), None);
+    }
+}
@@ -0,0 +1,23 @@
+mod cache;
+mod diagnostic;
+mod error;
+mod frame;
+mod library;
+mod program;
+mod reflect;
+mod source;
+mod std140;
+mod uniform_buffer;
+mod variants;
+
+pub use cache::ProgramCache;
+pub use diagnostic::Diagnostic;
+pub use error::{ShaderError, UniformError};
+pub use frame::FrameUniforms;
+pub use library::ShaderLibrary;
+pub use program::ShaderProgram;
+pub use reflect::{Attribute, Uniform};
+pub use source::Source;
+pub use std140::{AsStd140, Std140};
+pub use uniform_buffer::UniformBuffer;
+pub use variants::ShaderVariants;
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::{Backend, Program, Shader, UniformValue};
+use crate::utils;
+use super::{Attribute, ShaderError, Source, Uniform, UniformError};
+
+/// A linked program with its active uniforms and attributes looked up
+/// once. Array uniforms are keyed without their `[0]` suffix and `set`
+/// writes their first element.
+pub struct ShaderProgram {
+    gl: Rc<dyn Backend>,
+    program: Program,
+    uniforms: HashMap<String, Uniform>,
+    attributes: HashMap<String, Attribute>,
+    blocks: HashMap<String, u32>,
+    /// Stages shared through `ProgramCache`, released after the program.
+    stages: Vec<Rc<CompiledShader>>,
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        self.gl.delete_program(Some(&self.program));
+    }
+}
+
+/// A compiled shader object along with its code, deleted once the last
+/// program linked from it drops.
+pub(super) struct CompiledShader {
+    gl: Rc<dyn Backend>,
+    shader: Shader,
+    pub code: String,
+}
+
+impl Drop for CompiledShader {
+    fn drop(&mut self) {
+        self.gl.delete_shader(Some(&self.shader));
+    }
+}
+
+impl CompiledShader {
+    pub fn create(gl: &Rc<dyn Backend>, type_: u32, source: &Source) -> Result<Self, ShaderError> {
+        let shader = utils::create_shader(gl.as_ref(), type_, source)?;
+        Ok(Self { gl: gl.clone(), shader, code: source.code.clone() })
+    }
+}
+
+impl ShaderProgram {
+    /// Compiles and links plain sources, errors name them `vertex` and
+    /// `fragment`.
+    pub fn create(gl: &Rc<dyn Backend>, vs: &str, fs: &str) -> Result<Self, ShaderError> {
+        Self::build(gl, &Source::plain("vertex", vs), &Source::plain("fragment", fs))
+    }
+
+    /// Compiles and links both stages, the shader objects are released
+    /// once linked.
+    pub fn build(gl: &Rc<dyn Backend>, vs: &Source, fs: &Source) -> Result<Self, ShaderError> {
+        let v_shader = utils::create_shader(gl.as_ref(), WebGl::VERTEX_SHADER, vs)?;
+        let f_shader = match utils::create_shader(gl.as_ref(), WebGl::FRAGMENT_SHADER, fs) {
+            Ok(shader) => shader,
+            Err(e) => {
+                gl.delete_shader(Some(&v_shader));
+                return Err(e);
+            }
+        };
+        let program = utils::create_program(gl.as_ref(), &v_shader, &f_shader);
+        gl.delete_shader(Some(&v_shader));
+        gl.delete_shader(Some(&f_shader));
+        Ok(Self::wrap(gl.clone(), program?))
+    }
+
+    /// Links compiled stages that other programs may share, they are kept
+    /// alive as long as the program.
+    pub(super) fn link(
+        gl: &Rc<dyn Backend>,
+        vs: Rc<CompiledShader>,
+        fs: Rc<CompiledShader>,
+    ) -> Result<Self, ShaderError> {
+        let program = utils::create_program(gl.as_ref(), &vs.shader, &fs.shader)?;
+        let mut program = Self::wrap(gl.clone(), program);
+        program.stages = vec![vs, fs];
+        Ok(program)
+    }
+
+    /// Takes ownership of an already linked program and reflects it.
+    /// Members of uniform blocks have no location and are left out.
+    pub fn wrap(gl: Rc<dyn Backend>, program: Program) -> Self {
+        let uniforms = gl.active_uniforms(&program)
+            .into_iter()
+            .filter_map(|info| {
+                let location = gl.get_uniform_location(&program, &info.name)?;
+                let name = info.name.strip_suffix("[0]").unwrap_or(&info.name).to_owned();
+                Some((name, Uniform { location: Some(location), type_: info.type_, size: info.size }))
+            })
+            .collect();
+        let attributes = gl.active_attributes(&program)
+            .into_iter()
+            .map(|info| {
+                let location = gl.get_attrib_location(&program, &info.name);
+                (info.name, Attribute { location, type_: info.type_, size: info.size })
+            })
+            .collect();
+        let blocks = gl.active_uniform_blocks(&program)
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (name, i as u32))
+            .collect();
+        Self { gl, program, uniforms, attributes, blocks, stages: Vec::new() }
+    }
+}
+
+impl ShaderProgram {
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Stages linked through `link`, empty otherwise.
+    pub(super) fn stages(&self) -> &[Rc<CompiledShader>] {
+        &self.stages
+    }
+
+    pub fn bind(&self) {
+        self.gl.use_program(Some(&self.program));
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<&Uniform> {
+        self.uniforms.get(name.strip_suffix("[0]").unwrap_or(name))
+    }
+
+    pub fn uniforms(&self) -> impl Iterator<Item = (&str, &Uniform)> {
+        self.uniforms.iter().map(|(name, u)| (name.as_str(), u))
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.get(name)
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &Attribute)> {
+        self.attributes.iter().map(|(name, a)| (name.as_str(), a))
+    }
+
+    /// Index of an active uniform block.
+    pub fn block(&self, name: &str) -> Option<u32> {
+        self.blocks.get(name).copied()
+    }
+
+    /// Points the block `name` at a buffer binding, false if the program
+    /// has no such active block.
+    pub fn bind_block(&self, name: &str, binding: u32) -> bool {
+        match self.block(name) {
+            Some(index) => {
+                self.gl.uniform_block_binding(&self.program, index, binding);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Writes a uniform of the bound program after checking that it is
+    /// active and declared with a matching type.
+    pub fn set(&self, name: &str, value: impl Into<UniformValue>) -> Result<(), UniformError> {
+        let value = value.into();
+        let uniform = self.uniform(name).ok_or_else(|| UniformError::Unknown(name.to_owned()))?;
+        if !value.fits(uniform.type_) {
+            return Err(UniformError::TypeMismatch {
+                name: name.to_owned(),
+                expected: uniform.type_,
+                found: value.gl_type(),
+            });
+        }
+        self.gl.uniform(uniform.location.as_ref(), &value);
+        Ok(())
+    }
+}
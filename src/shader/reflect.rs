@@ -0,0 +1,17 @@
+use crate::backend::UniformLocation;
+
+/// An active uniform as reported after linking.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Uniform {
+    pub location: Option<UniformLocation>,
+    pub type_: u32,
+    pub size: i32,
+}
+
+/// An active vertex attribute as reported after linking.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Attribute {
+    pub location: i32,
+    pub type_: u32,
+    pub size: i32,
+}
@@ -0,0 +1,48 @@
+/// GLSL code along with the file and line each of its lines came from,
+/// so compiler messages can point past `#include`s.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Source {
+    pub code: String,
+    origins: Vec<(String, usize)>,
+}
+
+impl Source {
+    /// Code that was not preprocessed, every line maps to itself.
+    pub fn plain(file: &str, code: &str) -> Self {
+        Self {
+            code: code.to_owned(),
+            origins: (1..=code.lines().count()).map(|i| (file.to_owned(), i)).collect(),
+        }
+    }
+
+    pub(super) fn empty() -> Self {
+        Self { code: String::new(), origins: Vec::new() }
+    }
+
+    pub(super) fn push(&mut self, line: &str, file: &str, number: usize) {
+        self.code.push_str(line);
+        self.code.push('\n');
+        self.origins.push((file.to_owned(), number));
+    }
+
+    /// Inserts `lines` before line `at` (0-based), attributed to `file`.
+    pub(super) fn insert(&mut self, at: usize, lines: &[String], file: &str) {
+        let offset = self.code.lines().take(at).map(|l| l.len() + 1).sum();
+        let text: String = lines.iter().map(|l| format!("{}\n", l)).collect();
+        self.code.insert_str(offset, &text);
+        let origins = (1..=lines.len()).map(|i| (file.to_owned(), i));
+        self.origins.splice(at..at, origins);
+    }
+}
+
+impl Source {
+    /// File and line that produced `line` (1-based) of the code.
+    pub fn origin(&self, line: usize) -> Option<(&str, usize)> {
+        let (file, number) = self.origins.get(line.checked_sub(1)?)?;
+        Some((file.as_str(), *number))
+    }
+
+    pub fn line(&self, line: usize) -> Option<&str> {
+        self.code.lines().nth(line.checked_sub(1)?)
+    }
+}
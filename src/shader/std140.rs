@@ -0,0 +1,203 @@
+use crate::glm::{Mat3, Mat4, Vec2, Vec3, Vec4};
+
+/// Types that can be written into a uniform block.
+pub trait AsStd140 {
+    fn write_std140(&self, out: &mut Std140);
+
+    fn std140_bytes(&self) -> Vec<u8> {
+        let mut out = Std140::default();
+        self.write_std140(&mut out);
+        out.finish()
+    }
+}
+
+/// Packs values in declaration order with the std140 rules: scalars align
+/// to 4 bytes, `vec2` to 8, `vec3`, `vec4`, matrix columns, array elements
+/// and structs to 16.
+#[derive(Default)]
+pub struct Std140 {
+    data: Vec<u8>,
+}
+
+impl Std140 {
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The bytes padded to a whole number of `vec4`s, as block sizes are.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.align(16);
+        self.data
+    }
+
+    pub fn align(&mut self, alignment: usize) -> &mut Self {
+        let padded = self.data.len().div_ceil(alignment) * alignment;
+        self.data.resize(padded, 0);
+        self
+    }
+
+    fn put(&mut self, alignment: usize, values: &[f32]) -> &mut Self {
+        self.align(alignment);
+        self.data.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+        self
+    }
+}
+
+impl Std140 {
+    pub fn float(&mut self, v: f32) -> &mut Self {
+        self.put(4, &[v])
+    }
+
+    pub fn int(&mut self, v: i32) -> &mut Self {
+        self.align(4);
+        self.data.extend(v.to_le_bytes());
+        self
+    }
+
+    pub fn uint(&mut self, v: u32) -> &mut Self {
+        self.align(4);
+        self.data.extend(v.to_le_bytes());
+        self
+    }
+
+    pub fn bool(&mut self, v: bool) -> &mut Self {
+        self.uint(v as u32)
+    }
+
+    pub fn vec2(&mut self, v: &Vec2) -> &mut Self {
+        self.put(8, &[v.x, v.y])
+    }
+
+    pub fn vec3(&mut self, v: &Vec3) -> &mut Self {
+        self.put(16, &[v.x, v.y, v.z])
+    }
+
+    pub fn vec4(&mut self, v: &Vec4) -> &mut Self {
+        self.put(16, &[v.x, v.y, v.z, v.w])
+    }
+
+    /// Three columns, each padded to a `vec4`.
+    pub fn mat3(&mut self, m: &Mat3) -> &mut Self {
+        for column in m.chunks(3) {
+            self.put(16, column);
+        }
+        self.align(16)
+    }
+
+    pub fn mat4(&mut self, m: &Mat4) -> &mut Self {
+        self.put(16, m)
+    }
+
+    /// A `float[]`, every element takes a whole `vec4` slot.
+    pub fn floats(&mut self, values: &[f32]) -> &mut Self {
+        for v in values {
+            self.put(16, &[*v]);
+        }
+        self.align(16)
+    }
+
+    /// A nested struct, or one element of an array of structs.
+    pub fn nested(&mut self, value: &impl AsStd140) -> &mut Self {
+        self.align(16);
+        value.write_std140(self);
+        self.align(16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shader::FrameUniforms;
+    use super::*;
+
+    fn float(bytes: &[u8], at: usize) -> f32 {
+        f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    struct Light {
+        color: Vec3,
+        on: bool,
+    }
+
+    impl AsStd140 for Light {
+        fn write_std140(&self, out: &mut Std140) {
+            out.vec3(&self.color).bool(self.on);
+        }
+    }
+
+    #[test]
+    fn frame_uniforms_match_the_block() {
+        let frame = FrameUniforms {
+            projection: Mat4::from_scale(&Vec3::wrap(2.0, 2.0, 2.0)),
+            view: Mat4::from_translation(&Vec3::wrap(0.0, 0.0, -6.0)),
+            camera_position: Vec3::wrap(1.0, 2.0, 6.0),
+            time: 2.5,
+        };
+        let bytes = frame.std140_bytes();
+        assert_eq!(bytes.len(), 144);
+        assert_eq!(float(&bytes, 0), 2.0);
+        assert_eq!(float(&bytes, 64 + 14 * 4), -6.0);
+        assert_eq!([128, 132, 136].map(|i| float(&bytes, i)), [1.0, 2.0, 6.0]);
+        assert_eq!(float(&bytes, 140), 2.5);
+    }
+
+    #[test]
+    fn float_after_vec3_shares_its_slot() {
+        let mut out = Std140::default();
+        out.vec3(&Vec3::wrap(1.0, 2.0, 3.0)).float(4.0);
+        assert_eq!(out.len(), 16);
+        let mut out = Std140::default();
+        out.float(1.0)
+            .vec2(&Vec2::wrap(2.0, 3.0))
+            .float(4.0)
+            .vec3(&Vec3::wrap(5.0, 6.0, 7.0))
+            .float(8.0);
+        let bytes = out.finish();
+        // float, pad, vec2, float, pad to 32, vec3 + float.
+        assert_eq!(bytes.len(), 48);
+        let at = [8, 16, 32, 44].map(|i| float(&bytes, i));
+        assert_eq!(at, [2.0, 4.0, 5.0, 8.0]);
+    }
+
+    #[test]
+    fn mat3_columns_pad_to_vec4() {
+        let m = Mat3::from_mat4_normal(&Mat4::from_scale(&Vec3::wrap(2.0, 4.0, 8.0)));
+        let mut out = Std140::default();
+        out.float(9.0).mat3(&m).float(1.0);
+        let bytes = out.finish();
+        assert_eq!(bytes.len(), 16 + 48 + 16);
+        for column in 0..3 {
+            let at = 16 + column * 16;
+            for row in 0..3 {
+                assert_eq!(float(&bytes, at + row * 4), m[column * 3 + row]);
+            }
+            assert_eq!(float(&bytes, at + 12), 0.0);
+        }
+        assert_eq!(float(&bytes, 64), 1.0);
+    }
+
+    #[test]
+    fn float_arrays_use_a_vec4_stride() {
+        let mut out = Std140::default();
+        out.floats(&[1.0, 2.0, 3.0]).float(4.0);
+        let bytes = out.finish();
+        assert_eq!(bytes.len(), 64);
+        assert_eq!([0, 16, 32, 48].map(|i| float(&bytes, i)), [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn nested_structs_align_to_vec4() {
+        let light = Light { color: Vec3::wrap(1.0, 0.5, 0.25), on: true };
+        let mut out = Std140::default();
+        out.float(3.0).nested(&light).float(4.0);
+        let bytes = out.finish();
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(float(&bytes, 16), 1.0);
+        assert_eq!(u32::from_le_bytes(bytes[28..32].try_into().unwrap()), 1);
+        assert_eq!(float(&bytes, 32), 4.0);
+        assert_eq!(light.std140_bytes().len(), 16);
+    }
+}
@@ -0,0 +1,51 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use web_sys::WebGl2RenderingContext as WebGl;
+use crate::backend::{Backend, Buffer};
+use super::AsStd140;
+
+/// A GL buffer backing a uniform block at a fixed binding point.
+pub struct UniformBuffer {
+    gl: Rc<dyn Backend>,
+    buffer: Option<Buffer>,
+    binding: u32,
+    size: Cell<usize>,
+}
+
+impl Drop for UniformBuffer {
+    fn drop(&mut self) {
+        self.gl.delete_buffer(self.buffer.as_ref());
+    }
+}
+
+impl UniformBuffer {
+    pub fn create(gl: &Rc<dyn Backend>, binding: u32) -> Self {
+        Self {
+            gl: gl.clone(),
+            buffer: gl.create_buffer(),
+            binding,
+            size: Cell::new(0),
+        }
+    }
+}
+
+impl UniformBuffer {
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+
+    /// Uploads `value`, reallocating when its size changed, and attaches
+    /// the buffer to its binding point.
+    pub fn update(&self, value: &impl AsStd140) {
+        let data = value.std140_bytes();
+        self.gl.bind_buffer(WebGl::UNIFORM_BUFFER, self.buffer.as_ref());
+        if data.len() == self.size.get() {
+            self.gl.buffer_sub_data(WebGl::UNIFORM_BUFFER, 0, &data);
+        } else {
+            self.gl.buffer_data(WebGl::UNIFORM_BUFFER, &data, WebGl::DYNAMIC_DRAW);
+            self.size.set(data.len());
+        }
+        self.gl.bind_buffer(WebGl::UNIFORM_BUFFER, None);
+        self.gl.bind_buffer_base(WebGl::UNIFORM_BUFFER, self.binding, self.buffer.as_ref());
+    }
+}
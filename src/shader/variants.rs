@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use super::{ProgramCache, ShaderError, ShaderLibrary, ShaderProgram, Source};
+
+/// One vertex/fragment source pair compiled once per set of features.
+/// Each feature becomes `#define <FEATURE> 1`, so sources branch on them
+/// with `#ifdef`.
+pub struct ShaderVariants {
+    names: [String; 2],
+    vs: String,
+    fs: String,
+    defines: Vec<(String, String)>,
+    cache: RefCell<HashMap<Vec<String>, Rc<ShaderProgram>>>,
+}
+
+impl ShaderVariants {
+    pub fn wrap(vs: &str, fs: &str) -> Self {
+        Self::named("vertex", vs, "fragment", fs)
+    }
+
+    /// Sources reported under the given file names in errors.
+    pub fn named(vs_name: &str, vs: &str, fs_name: &str, fs: &str) -> Self {
+        Self {
+            names: [vs_name.to_owned(), fs_name.to_owned()],
+            vs: vs.to_owned(),
+            fs: fs.to_owned(),
+            defines: Vec::new(),
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl ShaderVariants {
+    /// Adds a define shared by every variant, built variants are dropped.
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.defines.retain(|(n, _)| n != name);
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self.cache.borrow_mut().clear();
+    }
+
+    /// Preprocessed vertex and fragment sources for `features`.
+    pub fn sources(&self, library: &ShaderLibrary, features: &[&str]) -> Result<(Source, Source), String> {
+        let key = Self::key(features);
+        let mut defines: Vec<(&str, &str)> = self.defines.iter()
+            .map(|(n, v)| (n.as_str(), v.as_str()))
+            .collect();
+        defines.extend(key.iter().map(|f| (f.as_str(), "1")));
+        Ok((
+            library.preprocess(&self.names[0], &self.vs, &defines)?,
+            library.preprocess(&self.names[1], &self.fs, &defines)?,
+        ))
+    }
+
+    /// The program for `features` in any order, taken from `programs` on
+    /// first use.
+    pub fn program(
+        &self,
+        programs: &ProgramCache,
+        library: &ShaderLibrary,
+        features: &[&str],
+    ) -> Result<Rc<ShaderProgram>, ShaderError> {
+        let key = Self::key(features);
+        if let Some(program) = self.cache.borrow().get(&key) {
+            return Ok(program.clone());
+        }
+        let (vs, fs) = self.sources(library, features).map_err(ShaderError::Preprocess)?;
+        let program = programs.get(&vs, &fs)?;
+        self.cache.borrow_mut().insert(key, program.clone());
+        Ok(program)
+    }
+
+    fn key(features: &[&str]) -> Vec<String> {
+        let mut key: Vec<String> = features.iter().map(|f| (*f).to_owned()).collect();
+        key.sort();
+        key.dedup();
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{Backend, Headless};
+    use super::*;
+
+    fn variants() -> ShaderVariants {
+        ShaderVariants::named(
+            "quad.vert", include_str!("glsl/quad.vert"),
+            "quad.frag", include_str!("glsl/quad.frag"),
+        )
+    }
+
+    #[test]
+    fn feature_order_does_not_matter() {
+        let gl: Rc<dyn Backend> = Rc::new(Headless::default());
+        let (programs, library) = (ProgramCache::wrap(gl), ShaderLibrary::builtin());
+        let variants = variants();
+        let a = variants.program(&programs, &library, &["LIT", "TEXTURED"]).unwrap();
+        let b = variants.program(&programs, &library, &["TEXTURED", "LIT"]).unwrap();
+        let c = variants.program(&programs, &library, &["TEXTURED", "LIT", "TEXTURED"]).unwrap();
+        assert!(Rc::ptr_eq(&a, &b) && Rc::ptr_eq(&a, &c));
+        let plain = variants.program(&programs, &library, &[]).unwrap();
+        assert!(!Rc::ptr_eq(&a, &plain));
+        assert!(a.uniform("uSampler").is_some());
+        assert!(plain.uniform("uSampler").is_none());
+        assert_eq!(programs.len(), 2);
+    }
+
+    #[test]
+    fn sources_define_each_feature() {
+        let mut variants = variants();
+        variants.define("MAX_LIGHTS", "4");
+        let (vs, fs) = variants.sources(&ShaderLibrary::builtin(), &["TEXTURED", "LIT"]).unwrap();
+        for source in [&vs, &fs] {
+            let lines: Vec<&str> = source.code.lines().take(4).collect();
+            assert_eq!(lines[1..], ["#define MAX_LIGHTS 4", "#define LIT 1", "#define TEXTURED 1"]);
+        }
+        assert_eq!(vs.origin(1), Some(("quad.vert", 1)));
+    }
+}